@@ -0,0 +1,84 @@
+use std::process::Command;
+
+use crate::{
+    error::{Error, Result},
+    keyboard_macro::Step,
+};
+
+#[cfg(test)]
+mod test;
+
+pub mod kitty;
+pub mod tmux;
+pub mod wezterm;
+pub mod zellij;
+
+/// Where Helix runs and how macros reach it.
+/// Default to the desktop of the platform, `windows` on Windows and `x11` elsewhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum BackendKind {
+    /// Desktop window, macros are typed with simulated keyboard input.
+    #[cfg_attr(windows, default)]
+    Windows,
+    /// Pane of a tmux server, macros are sent with `tmux send-keys`.
+    Tmux,
+    /// WezTerm pane, macros are sent with `wezterm cli send-text`.
+    Wezterm,
+    /// kitty window, macros are sent with the remote control protocol.
+    Kitty,
+    /// Pane of a zellij session, macros are sent with `zellij action write-chars`.
+    Zellij,
+    /// Window of an X11 desktop, macros are typed with XTest.
+    #[cfg_attr(not(windows), default)]
+    X11,
+}
+
+/// Helix running inside a terminal multiplexer or terminal that can be driven directly,
+/// without simulating keyboard input.
+pub trait Backend {
+    /// Find the Helix pane and focus it, returning its description.
+    /// Fails with [`Error::WindowNotFound`] when there is none.
+    fn focus(&mut self) -> Result<String>;
+
+    /// Whether there is a pane `focus` would find, leaving focus where it is,
+    /// to wait for a launched Helix without moving the user around.
+    fn is_open(&self) -> Result<bool>;
+
+    /// Send `steps` to the pane found by `focus`.
+    fn send(&mut self, steps: &[Step]) -> Result<()>;
+}
+
+/// Text a terminal sends to Helix for `step`.
+///
+/// Send each step separately, Helix reads an escape directly followed by a key as Alt+key.
+pub fn step_text(step: &Step) -> String {
+    match step {
+        Step::Escape => "\x1b".to_owned(),
+        Step::Command(command) => format!(":{command}\r"),
+        Step::Keys(keys) => keys.clone(),
+    }
+}
+
+/// First of `panes` matching `is_match`, preferring one for which `is_active` holds
+/// so the pane the user was last in is used when several match.
+pub(crate) fn find_match<T>(
+    panes: Vec<T>,
+    is_match: impl Fn(&T) -> bool,
+    is_active: impl Fn(&T) -> bool,
+) -> Option<T> {
+    let mut panes: Vec<_> = panes.into_iter().filter(|pane| is_match(pane)).collect();
+    panes.sort_by_key(|pane| !is_active(pane));
+    panes.into_iter().next()
+}
+
+/// Run `command` and return its standard output.
+/// Fails with its standard error when it exits unsuccessfully.
+pub(crate) fn output(command: &mut Command) -> Result<String> {
+    let output = command.output()?;
+    if !output.status.success() {
+        let program = command.get_program().to_string_lossy().into_owned();
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+        return Err(Error::Backend(format!("`{program}` failed: {message}")));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
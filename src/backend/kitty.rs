@@ -0,0 +1,219 @@
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+use serde_json::{json, Value};
+
+use super::{find_match, step_text, Backend};
+use crate::{
+    error::{Error, Result},
+    keyboard_macro::Step,
+    matcher::WindowMatcher,
+};
+
+#[cfg(test)]
+mod test;
+
+const PREFIX: &[u8] = b"\x1bP@kitty-cmd";
+const SUFFIX: &[u8] = b"\x1b\\";
+
+/// Helix in a kitty window, driven with the remote control protocol.
+///
+/// kitty must be started with `--listen-on` and `allow_remote_control`.
+/// Windows are matched with the program running in them as the process name and the window title.
+#[derive(Debug, Clone)]
+pub struct Kitty {
+    /// Address kitty listens on, `unix:<path>` or `tcp:<host>:<port>`.
+    pub address: String,
+    pub matcher: WindowMatcher,
+    /// How long to wait for kitty to answer a command.
+    pub timeout: Duration,
+    /// Window found by `focus`.
+    window_id: Option<u64>,
+}
+
+/// Window listed by `ls`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KittyWindow {
+    pub id: u64,
+    pub title: String,
+    /// Program running in the window, the foreground one if known.
+    pub program: String,
+    pub is_focused: bool,
+}
+
+/// Windows in the `data` of an `ls` response, which is itself JSON.
+pub fn parse_windows(data: &str) -> Result<Vec<KittyWindow>> {
+    let invalid = |e: String| Error::Backend(format!("invalid kitty `ls` response: {e}"));
+    let os_windows: Value = serde_json::from_str(data).map_err(|e| invalid(e.to_string()))?;
+    let as_array = |value: &Value| value.as_array().cloned().unwrap_or_default();
+    let program = |cmdline: &Value| {
+        let program = cmdline.get(0)?.as_str()?;
+        program.rsplit(['/', '\\']).next().map(str::to_owned)
+    };
+    let mut windows = vec![];
+    for os_window in as_array(&os_windows) {
+        for tab in as_array(&os_window["tabs"]) {
+            for window in as_array(&tab["windows"]) {
+                let id = window["id"]
+                    .as_u64()
+                    .ok_or_else(|| invalid("window without id".to_owned()))?;
+                let foreground = as_array(&window["foreground_processes"])
+                    .last()
+                    .and_then(|process| program(&process["cmdline"]));
+                windows.push(KittyWindow {
+                    id,
+                    title: window["title"].as_str().unwrap_or_default().to_owned(),
+                    program: foreground
+                        .or_else(|| program(&window["cmdline"]))
+                        .unwrap_or_default(),
+                    is_focused: window["is_focused"].as_bool().unwrap_or(false),
+                });
+            }
+        }
+    }
+    Ok(windows)
+}
+
+/// Remote control request for `cmd`.
+pub fn encode_command(cmd: &str, payload: Value) -> Vec<u8> {
+    let message = json!({
+        "cmd": cmd,
+        "version": [0, 26, 0],
+        "payload": payload,
+    });
+    [PREFIX, message.to_string().as_bytes(), SUFFIX].concat()
+}
+
+/// `data` of a remote control response, failing with its `error` when not `ok`.
+pub fn decode_response(response: &[u8]) -> Result<Option<Value>> {
+    let invalid = || Error::Backend("invalid kitty response".to_owned());
+    let message = response
+        .strip_prefix(PREFIX)
+        .and_then(|message| message.strip_suffix(SUFFIX))
+        .ok_or_else(invalid)?;
+    let message: Value = serde_json::from_slice(message).map_err(|_| invalid())?;
+    if message["ok"].as_bool() != Some(true) {
+        let error = message["error"].as_str().unwrap_or("unknown error");
+        return Err(Error::Backend(format!("kitty: {error}")));
+    }
+    Ok(message.get("data").cloned())
+}
+
+trait Stream: Read + Write {}
+impl<T: Read + Write> Stream for T {}
+
+/// Answer timeout when not set otherwise.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+impl Kitty {
+    pub fn new(address: String, matcher: WindowMatcher) -> Kitty {
+        Kitty {
+            address,
+            matcher,
+            timeout: TIMEOUT,
+            window_id: None,
+        }
+    }
+
+    fn connect(&self) -> Result<Box<dyn Stream>> {
+        if let Some(address) = self.address.strip_prefix("tcp:") {
+            let stream = TcpStream::connect(address)?;
+            stream.set_read_timeout(Some(self.timeout))?;
+            return Ok(Box::new(stream));
+        }
+        #[cfg(unix)]
+        if let Some(path) = self.address.strip_prefix("unix:") {
+            let stream = std::os::unix::net::UnixStream::connect(path)?;
+            stream.set_read_timeout(Some(self.timeout))?;
+            return Ok(Box::new(stream));
+        }
+        Err(Error::Backend(format!(
+            "unsupported kitty address `{}`",
+            self.address
+        )))
+    }
+
+    /// Send a command and return the `data` of its response.
+    fn command(&self, cmd: &str, payload: Value) -> Result<Option<Value>> {
+        let mut stream = self.connect()?;
+        stream.write_all(&encode_command(cmd, payload))?;
+        stream.flush()?;
+        let mut response = vec![];
+        let mut buffer = [0; 4096];
+        while !response.ends_with(SUFFIX) {
+            let n = stream.read(&mut buffer).map_err(|e| match e.kind() {
+                // Timing out gives `WouldBlock` on Unix and `TimedOut` on Windows.
+                ErrorKind::WouldBlock | ErrorKind::TimedOut => Error::Backend(format!(
+                    "kitty did not answer `{cmd}` within {} seconds",
+                    self.timeout.as_secs_f64()
+                )),
+                _ => Error::Io(e),
+            })?;
+            if n == 0 {
+                break;
+            }
+            response.extend_from_slice(&buffer[..n]);
+        }
+        decode_response(&response)
+    }
+
+    pub fn windows(&self) -> Result<Vec<KittyWindow>> {
+        let data = self.command("ls", json!({}))?;
+        parse_windows(data.as_ref().and_then(Value::as_str).unwrap_or("[]"))
+    }
+
+    /// Matching kitty window, preferring the one with keyboard focus.
+    pub fn find_window(&self) -> Result<Option<KittyWindow>> {
+        Ok(find_match(
+            self.windows()?,
+            |window| self.matcher.is_match(&window.program, &window.title),
+            |window| window.is_focused,
+        ))
+    }
+
+    /// Like `find_window`, with no window when kitty isn't running yet.
+    fn find_running_window(&self) -> Result<Option<KittyWindow>> {
+        match self.find_window() {
+            // Nothing listening means no kitty running yet.
+            Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => Ok(None),
+            res => res,
+        }
+    }
+}
+
+impl Backend for Kitty {
+    fn focus(&mut self) -> Result<String> {
+        let window = self.find_running_window()?.ok_or(Error::WindowNotFound)?;
+        self.command(
+            "focus-window",
+            json!({ "match": format!("id:{}", window.id) }),
+        )?;
+        self.window_id = Some(window.id);
+        Ok(format!(
+            "kitty window {} [{}] {}",
+            window.id, window.program, window.title
+        ))
+    }
+
+    fn is_open(&self) -> Result<bool> {
+        Ok(self.find_running_window()?.is_some())
+    }
+
+    fn send(&mut self, steps: &[Step]) -> Result<()> {
+        let window_id = self.window_id.ok_or(Error::WindowNotFound)?;
+        for step in steps {
+            self.command(
+                "send-text",
+                json!({
+                    "match": format!("id:{window_id}"),
+                    "data": format!("text:{}", step_text(step)),
+                }),
+            )?;
+        }
+        Ok(())
+    }
+}
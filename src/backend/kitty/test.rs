@@ -0,0 +1,162 @@
+use serde_json::{json, Value};
+
+use super::{decode_response, encode_command, parse_windows, KittyWindow};
+
+const LS: &str = r#"[{
+  "id": 1,
+  "tabs": [{
+    "id": 1,
+    "windows": [
+      {
+        "id": 1,
+        "title": "~",
+        "is_focused": true,
+        "cmdline": ["/bin/zsh"],
+        "foreground_processes": [{ "pid": 10, "cmdline": ["/bin/zsh"] }]
+      },
+      {
+        "id": 2,
+        "title": "Helix my_game",
+        "is_focused": false,
+        "cmdline": ["/bin/zsh"],
+        "foreground_processes": [{ "pid": 11, "cmdline": ["/usr/bin/hx", "a.gd"] }]
+      }
+    ]
+  }]
+}]"#;
+
+#[test]
+fn test_parse_windows() {
+    let windows = parse_windows(LS).unwrap();
+    assert_eq!(
+        windows[1],
+        KittyWindow {
+            id: 2,
+            title: "Helix my_game".to_owned(),
+            program: "hx".to_owned(),
+            is_focused: false,
+        }
+    );
+    assert_eq!(windows[0].program, "zsh");
+    assert!(parse_windows("{").is_err());
+}
+
+#[test]
+fn test_protocol() {
+    let request = encode_command("ls", json!({}));
+    let request = std::str::from_utf8(&request).unwrap();
+    let message = request
+        .strip_prefix("\x1bP@kitty-cmd")
+        .and_then(|message| message.strip_suffix("\x1b\\"))
+        .unwrap();
+    let message: Value = serde_json::from_str(message).unwrap();
+    assert_eq!(message["cmd"], "ls");
+
+    let response = b"\x1bP@kitty-cmd{\"ok\": true, \"data\": \"[]\"}\x1b\\";
+    assert_eq!(decode_response(response).unwrap(), Some(json!("[]")));
+    let response = b"\x1bP@kitty-cmd{\"ok\": false, \"error\": \"no match\"}\x1b\\";
+    assert!(decode_response(response).is_err());
+    assert!(decode_response(b"garbage").is_err());
+}
+
+/// kitty replaced by a server answering on a unix socket.
+#[cfg(unix)]
+#[test]
+fn test_kitty_send() {
+    use std::{
+        io::{Read, Write},
+        os::unix::net::UnixListener,
+    };
+
+    use super::Kitty;
+    use crate::{
+        backend::Backend,
+        keyboard_macro::Step,
+        matcher::{MatchMode, Pattern, WindowMatcher},
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("kitty.sock");
+    let listener = UnixListener::bind(&path).unwrap();
+    let server = std::thread::spawn(move || {
+        let mut requests = vec![];
+        // `ls`, `focus-window` and one `send-text` per step.
+        for _ in 0..4 {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![];
+            let mut buffer = [0; 4096];
+            while !request.ends_with(b"\x1b\\") {
+                let n = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..n]);
+            }
+            let message: Value = serde_json::from_slice(&request[12..request.len() - 2]).unwrap();
+            let response = match message["cmd"].as_str() {
+                Some("ls") => json!({ "ok": true, "data": LS }),
+                _ => json!({ "ok": true }),
+            };
+            stream
+                .write_all(format!("\x1bP@kitty-cmd{response}\x1b\\").as_bytes())
+                .unwrap();
+            requests.push(message);
+        }
+        requests
+    });
+
+    let matcher = WindowMatcher {
+        process_name: Some(Pattern::new("hx", MatchMode::Exact, true).unwrap()),
+        ..Default::default()
+    };
+    let mut kitty = Kitty::new(format!("unix:{}", path.display()), matcher);
+    assert_eq!(kitty.focus().unwrap(), "kitty window 2 [hx] Helix my_game");
+    kitty
+        .send(&[Step::Escape, Step::Keys("12gg".to_owned())])
+        .unwrap();
+
+    let requests = server.join().unwrap();
+    let commands: Vec<_> = requests
+        .iter()
+        .map(|request| (request["cmd"].as_str().unwrap(), &request["payload"]))
+        .collect();
+    assert_eq!(
+        commands,
+        [
+            ("ls", &json!({})),
+            ("focus-window", &json!({ "match": "id:2" })),
+            (
+                "send-text",
+                &json!({ "match": "id:2", "data": "text:\x1b" })
+            ),
+            (
+                "send-text",
+                &json!({ "match": "id:2", "data": "text:12gg" })
+            ),
+        ]
+    );
+}
+
+/// kitty accepting the connection but never answering.
+#[cfg(unix)]
+#[test]
+fn test_kitty_timeout() {
+    use std::{os::unix::net::UnixListener, time::Duration};
+
+    use super::Kitty;
+    use crate::{backend::Backend, error::Error, matcher::WindowMatcher};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("kitty.sock");
+    let listener = UnixListener::bind(&path).unwrap();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        std::thread::sleep(Duration::from_millis(500));
+        drop(stream);
+    });
+
+    let mut kitty = Kitty::new(format!("unix:{}", path.display()), WindowMatcher::default());
+    kitty.timeout = Duration::from_millis(50);
+    match kitty.focus() {
+        Err(Error::Backend(message)) => assert!(message.contains("did not answer `ls`")),
+        res => panic!("expected a timeout, got {res:?}"),
+    }
+    server.join().unwrap();
+}
@@ -0,0 +1,39 @@
+use super::{find_match, step_text};
+use crate::keyboard_macro::Step;
+
+/// Executable `name` in `dir` running the shell script `body`,
+/// standing in for a terminal's CLI in backend tests.
+#[cfg(unix)]
+pub(crate) fn fake_program(dir: &std::path::Path, name: &str, body: &str) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let program = dir.join(name);
+    std::fs::write(&program, format!("#!/bin/sh\n{body}")).unwrap();
+    std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+    program
+}
+
+#[test]
+fn test_step_text() {
+    assert_eq!(step_text(&Step::Escape), "\x1b");
+    assert_eq!(
+        step_text(&Step::Command(r#"o "a.gd""#.to_owned())),
+        ":o \"a.gd\"\r"
+    );
+    assert_eq!(step_text(&Step::Keys("12gg".to_owned())), "12gg");
+}
+
+#[test]
+fn test_find_match() {
+    let panes = vec![("hx", false), ("zsh", true), ("hx", true), ("hx", false)];
+    let is_hx = |pane: &(&str, bool)| pane.0 == "hx";
+    let found = find_match(panes.clone(), is_hx, |pane| pane.1);
+    assert_eq!(found, Some(("hx", true)));
+    // Without an active match, the first match in listing order.
+    let found = find_match(panes[..1].to_vec(), is_hx, |pane| pane.1);
+    assert_eq!(found, Some(("hx", false)));
+    assert_eq!(
+        find_match(panes, |pane| pane.0 == "vim", |pane| pane.1),
+        None
+    );
+}
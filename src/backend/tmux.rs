@@ -0,0 +1,145 @@
+use std::process::Command;
+
+use super::{find_match, output, Backend};
+use crate::{
+    error::{Error, Result},
+    keyboard_macro::Step,
+    matcher::WindowMatcher,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Format of `list-panes`, one pane per line.
+const PANE_FORMAT: &str = "#{pane_id}\t#{session_name}\t#{window_name}\t#{pane_current_command}\t#{window_active}#{pane_active}";
+
+/// Helix in a tmux pane.
+///
+/// Panes are matched with the command running in them as the process name
+/// and `session:window` as the title.
+#[derive(Debug, Clone, Default)]
+pub struct Tmux {
+    /// Socket name given to `tmux -L`, or socket path given to `-S` when it has a path separator.
+    /// The default server when `None`.
+    pub socket: Option<String>,
+    pub matcher: WindowMatcher,
+    /// Pane found by `focus`.
+    pane: Option<String>,
+}
+
+/// Pane listed by `tmux list-panes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pane {
+    /// Like `%3`, unique in the server.
+    pub id: String,
+    pub session: String,
+    pub window: String,
+    /// Command running in the pane.
+    pub command: String,
+    /// Active pane of the active window in its session.
+    pub active: bool,
+}
+
+impl Pane {
+    /// Title to match, `session:window`.
+    pub fn title(&self) -> String {
+        format!("{}:{}", self.session, self.window)
+    }
+}
+
+/// Parse the output of `list-panes` with [`PANE_FORMAT`].
+pub fn parse_panes(output: &str) -> Vec<Pane> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            Some(Pane {
+                id: fields.next()?.to_owned(),
+                session: fields.next()?.to_owned(),
+                window: fields.next()?.to_owned(),
+                command: fields.next()?.to_owned(),
+                active: fields.next()? == "11",
+            })
+        })
+        .collect()
+}
+
+impl Tmux {
+    pub fn new(socket: Option<String>, matcher: WindowMatcher) -> Tmux {
+        Tmux {
+            socket,
+            matcher,
+            pane: None,
+        }
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new("tmux");
+        match &self.socket {
+            Some(socket) if socket.contains(['/', '\\']) => command.args(["-S", socket]),
+            Some(socket) => command.args(["-L", socket]),
+            None => &mut command,
+        };
+        command
+    }
+
+    fn tmux(&self, args: &[&str]) -> Result<String> {
+        output(self.command().args(args))
+    }
+
+    /// Panes in all sessions.
+    /// A socket without a tmux server behind it has no panes rather than failing.
+    pub fn panes(&self) -> Result<Vec<Pane>> {
+        match self.tmux(&["list-panes", "-a", "-F", PANE_FORMAT]) {
+            Ok(output) => Ok(parse_panes(&output)),
+            Err(Error::Backend(message))
+                if message.contains("no server running")
+                    || message.contains("error connecting") =>
+            {
+                Ok(vec![])
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Matching pane, preferring the active pane of a session's current window.
+    pub fn find_pane(&self) -> Result<Option<Pane>> {
+        Ok(find_match(
+            self.panes()?,
+            |pane| self.matcher.is_match(&pane.command, &pane.title()),
+            |pane| pane.active,
+        ))
+    }
+}
+
+impl Backend for Tmux {
+    fn focus(&mut self) -> Result<String> {
+        let pane = self.find_pane()?.ok_or(Error::WindowNotFound)?;
+        let description = format!("tmux pane {} in {}", pane.id, pane.title());
+        self.tmux(&["select-window", "-t", &pane.id])?;
+        self.tmux(&["select-pane", "-t", &pane.id])?;
+        // Fails when no client is attached, the pane is still selected for the next one.
+        let _ = self.tmux(&["switch-client", "-t", &pane.id]);
+        self.pane = Some(pane.id);
+        Ok(description)
+    }
+
+    fn is_open(&self) -> Result<bool> {
+        Ok(self.find_pane()?.is_some())
+    }
+
+    fn send(&mut self, steps: &[Step]) -> Result<()> {
+        let pane = self.pane.clone().ok_or(Error::WindowNotFound)?;
+        for step in steps {
+            match step {
+                Step::Escape => self.tmux(&["send-keys", "-t", &pane, "Escape"])?,
+                Step::Command(command) => {
+                    self.tmux(&["send-keys", "-t", &pane, "-l", &format!(":{command}")])?;
+                    self.tmux(&["send-keys", "-t", &pane, "Enter"])?
+                }
+                Step::Keys(keys) => self.tmux(&["send-keys", "-t", &pane, "-l", keys])?,
+            };
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use std::{
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use super::{parse_panes, Pane, Tmux, PANE_FORMAT};
+use crate::{
+    backend::Backend,
+    keyboard_macro::Step,
+    matcher::{MatchMode, Pattern, WindowMatcher},
+};
+
+#[test]
+fn test_parse_panes() {
+    let output = "%0\tmain\teditor\thx\t11\n%1\tmain\tshell\tbash\t01\n%2\tbroken\n";
+    assert_eq!(
+        parse_panes(output),
+        [
+            Pane {
+                id: "%0".to_owned(),
+                session: "main".to_owned(),
+                window: "editor".to_owned(),
+                command: "hx".to_owned(),
+                active: true,
+            },
+            Pane {
+                id: "%1".to_owned(),
+                session: "main".to_owned(),
+                window: "shell".to_owned(),
+                command: "bash".to_owned(),
+                active: false,
+            },
+        ]
+    );
+    assert_eq!(parse_panes(output)[0].title(), "main:editor");
+}
+
+/// tmux server on a socket in its own directory, killed when dropped.
+struct Server {
+    socket: String,
+    _dir: tempfile::TempDir,
+}
+
+impl Server {
+    /// Start a server with a session running `cat`, `None` when tmux is not installed.
+    fn start() -> Option<Server> {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("tmux").display().to_string();
+        let status = Command::new("tmux")
+            .args(["-S", &socket, "-f", "/dev/null", "new-session", "-d"])
+            .args(["-s", "test", "-n", "editor", "-x", "80", "-y", "24", "cat"])
+            .status()
+            .ok()?;
+        assert!(status.success());
+        let server = Server { socket, _dir: dir };
+        server.tmux(&["new-window", "-t", "test", "-n", "other", "sleep 60"]);
+        server.wait_for_pane("test:editor", "cat");
+        server.wait_for_pane("test:other", "sleep");
+        Some(server)
+    }
+
+    /// Wait until the panes listed like the backend does have one running `command` in `title`,
+    /// a new pane runs the shell at first.
+    fn wait_for_pane(&self, title: &str, command: &str) {
+        let start = Instant::now();
+        while !parse_panes(&self.tmux(&["list-panes", "-a", "-F", PANE_FORMAT]))
+            .iter()
+            .any(|pane| pane.title() == title && pane.command == command)
+        {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "`{title}` never ran `{command}`"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn tmux(&self, args: &[&str]) -> String {
+        let output = Command::new("tmux")
+            .args(["-S", &self.socket])
+            .args(args)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.tmux(&["kill-server"]);
+    }
+}
+
+#[test]
+fn test_tmux_send() {
+    let Some(server) = Server::start() else {
+        return;
+    };
+    let matcher = WindowMatcher {
+        process_name: Some(Pattern::new("cat", MatchMode::Exact, true).unwrap()),
+        ..Default::default()
+    };
+    let mut tmux = Tmux::new(Some(server.socket.clone()), matcher);
+
+    let description = tmux.focus().unwrap();
+    assert!(description.ends_with("in test:editor"));
+    assert_eq!(
+        server.tmux(&["display-message", "-p", "-t", "test", "#{window_name}"]),
+        "editor\n"
+    );
+
+    tmux.send(&[
+        Step::Command(r#"o "a b.gd""#.to_owned()),
+        Step::Keys("12gg".to_owned()),
+    ])
+    .unwrap();
+    let start = Instant::now();
+    loop {
+        let screen = server.tmux(&["capture-pane", "-p", "-t", "test:editor"]);
+        if screen.contains(r#":o "a b.gd""#) && screen.contains("12gg") {
+            break;
+        }
+        assert!(start.elapsed() < Duration::from_secs(5), "{screen}");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn test_tmux_not_found() {
+    let matcher = WindowMatcher {
+        process_name: Some(Pattern::new("hx", MatchMode::Exact, true).unwrap()),
+        ..Default::default()
+    };
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("tmux").display().to_string();
+    let mut tmux = Tmux::new(Some(socket), matcher);
+    if Command::new("tmux").arg("-V").output().is_err() {
+        return;
+    }
+    assert!(matches!(
+        tmux.focus(),
+        Err(crate::error::Error::WindowNotFound)
+    ));
+}
@@ -0,0 +1,111 @@
+use std::{path::PathBuf, process::Command};
+
+use super::{find_match, output, step_text, Backend};
+use crate::{
+    error::{Error, Result},
+    keyboard_macro::Step,
+    matcher::WindowMatcher,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Helix in a WezTerm pane, driven with `wezterm cli`.
+///
+/// Panes are matched with the pane title, which is the running program unless it sets one,
+/// as the process name and the window title as the title.
+#[derive(Debug, Clone)]
+pub struct Wezterm {
+    /// `wezterm` executable.
+    pub program: PathBuf,
+    pub matcher: WindowMatcher,
+    /// Pane found by `focus`.
+    pane_id: Option<u64>,
+}
+
+/// Pane listed by `wezterm cli list --format json`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct Pane {
+    pub pane_id: u64,
+    pub tab_id: u64,
+    pub window_id: u64,
+    #[serde(default)]
+    pub workspace: String,
+    pub title: String,
+    #[serde(default)]
+    pub window_title: String,
+    /// Active pane of its tab.
+    #[serde(default)]
+    pub is_active: bool,
+}
+
+/// Parse the output of `wezterm cli list --format json`.
+pub fn parse_panes(output: &str) -> Result<Vec<Pane>> {
+    serde_json::from_str(output)
+        .map_err(|e| Error::Backend(format!("invalid `wezterm cli list` output: {e}")))
+}
+
+impl Wezterm {
+    pub fn new(matcher: WindowMatcher) -> Wezterm {
+        Wezterm {
+            program: PathBuf::from("wezterm"),
+            matcher,
+            pane_id: None,
+        }
+    }
+
+    fn cli(&self, args: &[&str]) -> Result<String> {
+        output(Command::new(&self.program).arg("cli").args(args))
+    }
+
+    /// Panes of all windows and workspaces.
+    /// When `wezterm cli` can't connect to a mux server, WezTerm isn't open and there are no panes.
+    pub fn panes(&self) -> Result<Vec<Pane>> {
+        match self.cli(&["list", "--format", "json"]) {
+            Ok(output) => parse_panes(&output),
+            Err(Error::Backend(message)) if message.contains("connect") => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Matching pane, preferring one that is the active pane of its tab.
+    pub fn find_pane(&self) -> Result<Option<Pane>> {
+        Ok(find_match(
+            self.panes()?,
+            |pane| self.matcher.is_match(&pane.title, &pane.window_title),
+            |pane| pane.is_active,
+        ))
+    }
+}
+
+impl Backend for Wezterm {
+    fn focus(&mut self) -> Result<String> {
+        let pane = self.find_pane()?.ok_or(Error::WindowNotFound)?;
+        let pane_id = pane.pane_id.to_string();
+        self.cli(&["activate-pane", "--pane-id", &pane_id])?;
+        self.pane_id = Some(pane.pane_id);
+        Ok(format!(
+            "WezTerm pane {pane_id} [{}] {}",
+            pane.title, pane.window_title
+        ))
+    }
+
+    fn is_open(&self) -> Result<bool> {
+        Ok(self.find_pane()?.is_some())
+    }
+
+    fn send(&mut self, steps: &[Step]) -> Result<()> {
+        let pane_id = self.pane_id.ok_or(Error::WindowNotFound)?.to_string();
+        for step in steps {
+            // `--no-paste` sends the text as typed instead of a bracketed paste.
+            self.cli(&[
+                "send-text",
+                "--pane-id",
+                &pane_id,
+                "--no-paste",
+                &step_text(step),
+            ])?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use super::{parse_panes, Wezterm};
+use crate::{
+    backend::Backend,
+    keyboard_macro::Step,
+    matcher::{MatchMode, Pattern, WindowMatcher},
+};
+
+const LIST: &str = r#"[
+  {
+    "window_id": 0,
+    "tab_id": 0,
+    "pane_id": 0,
+    "workspace": "default",
+    "size": { "rows": 24, "cols": 80 },
+    "title": "zsh",
+    "cwd": "file:///home/user",
+    "window_title": "zsh",
+    "is_active": true
+  },
+  {
+    "window_id": 1,
+    "tab_id": 2,
+    "pane_id": 3,
+    "workspace": "default",
+    "title": "hx",
+    "window_title": "Helix my_game",
+    "is_active": false
+  }
+]"#;
+
+fn matcher(process_name: &str) -> WindowMatcher {
+    WindowMatcher {
+        process_name: Some(Pattern::new(process_name, MatchMode::Exact, true).unwrap()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_parse_panes() {
+    let panes = parse_panes(LIST).unwrap();
+    assert_eq!(panes.len(), 2);
+    assert_eq!(panes[1].pane_id, 3);
+    assert_eq!(panes[1].window_title, "Helix my_game");
+    assert!(parse_panes("not json").is_err());
+}
+
+/// `wezterm` replaced by a script logging its arguments.
+#[cfg(unix)]
+#[test]
+fn test_wezterm_send() {
+    use crate::backend::test::fake_program;
+
+    let dir = tempfile::tempdir().unwrap();
+    let list = dir.path().join("list.json");
+    let log = dir.path().join("log");
+    std::fs::write(&list, LIST).unwrap();
+    let script = format!(
+        "printf '%s|' \"$@\" >> '{log}'\necho >> '{log}'\n[ \"$2\" = list ] && cat '{list}'\nexit 0\n",
+        log = log.display(),
+        list = list.display()
+    );
+    let program = fake_program(dir.path(), "wezterm", &script);
+
+    let mut wezterm = Wezterm::new(matcher("hx"));
+    wezterm.program = program;
+    assert_eq!(
+        wezterm.focus().unwrap(),
+        "WezTerm pane 3 [hx] Helix my_game"
+    );
+    wezterm
+        .send(&[Step::Escape, Step::Command("o a.gd".to_owned())])
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&log).unwrap(),
+        "cli|list|--format|json|\n\
+         cli|activate-pane|--pane-id|3|\n\
+         cli|send-text|--pane-id|3|--no-paste|\x1b|\n\
+         cli|send-text|--pane-id|3|--no-paste|:o a.gd\r|\n"
+    );
+
+    wezterm.matcher = matcher("vim");
+    assert!(matches!(
+        wezterm.focus(),
+        Err(crate::error::Error::WindowNotFound)
+    ));
+}
@@ -0,0 +1,262 @@
+use std::{path::PathBuf, process::Command};
+
+use super::{output, Backend};
+use crate::{
+    error::{Error, Result},
+    keyboard_macro::Step,
+    matcher::WindowMatcher,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Panes tried with `focus-next-pane` before giving up.
+const MAX_PANES: usize = 32;
+
+/// Helix in a pane of a zellij session, driven with `zellij action`.
+///
+/// zellij can't focus a pane by id, so the focused pane shown by `list-clients` is checked
+/// while moving focus to the next pane until one matches.
+/// Panes are matched with the program running in them as the process name and the session name as the title,
+/// zellij doesn't report pane titles.
+/// Focus and tab are moved back to where they were when no pane matches.
+/// Waiting for a pane checks the commands of `dump-layout` instead, leaving focus alone.
+/// Needs zellij 0.40 or later and a client attached to the session.
+#[derive(Debug, Clone)]
+pub struct Zellij {
+    /// `zellij` executable.
+    pub program: PathBuf,
+    pub session: String,
+    /// Tab to go to before searching.
+    pub tab: Option<String>,
+    pub matcher: WindowMatcher,
+}
+
+/// Focused pane of a client, from `zellij action list-clients`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusedPane {
+    /// Like `terminal_2`.
+    pub pane_id: String,
+    /// Command line running in the pane, empty when unknown.
+    pub command: String,
+}
+
+impl FocusedPane {
+    /// File name of the program running in the pane.
+    pub fn program(&self) -> &str {
+        program(&self.command)
+    }
+}
+
+/// File name of the program of a command line.
+fn program(command: &str) -> &str {
+    let program = command.split_whitespace().next().unwrap_or_default();
+    program.rsplit(['/', '\\']).next().unwrap_or_default()
+}
+
+/// Tab of the session, from `zellij action dump-layout`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayoutTab {
+    pub name: String,
+    /// Tab shown to the user.
+    pub focused: bool,
+    /// Commands running in its panes, panes running the default shell have none.
+    pub commands: Vec<String>,
+}
+
+impl LayoutTab {
+    /// File names of the programs running in its panes.
+    pub fn programs(&self) -> impl Iterator<Item = &str> {
+        self.commands.iter().map(|command| program(command))
+    }
+}
+
+/// Value of `key="value"` among the properties of a KDL node.
+fn property<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let (_, rest) = line.split_once(&format!(" {key}=\""))?;
+    rest.split_once('"').map(|(value, _)| value)
+}
+
+/// Parse the KDL layout of `dump-layout`, keeping the tabs and leaving templates out.
+pub fn parse_layout(output: &str) -> Vec<LayoutTab> {
+    let mut tabs = vec![];
+    let mut depth = 0;
+    let mut tab: Option<LayoutTab> = None;
+    for line in output.lines() {
+        let line = line.trim();
+        // Tabs are children of `layout`, templates next to them also have panes.
+        if depth == 1 && (line == "tab" || line.starts_with("tab ")) {
+            tab = Some(LayoutTab {
+                name: property(line, "name").unwrap_or_default().to_owned(),
+                focused: line.contains(" focus=true"),
+                commands: vec![],
+            });
+        }
+        if let (Some(tab), true) = (&mut tab, line.starts_with("pane ")) {
+            if let Some(command) = property(line, "command") {
+                tab.commands.push(command.to_owned());
+            }
+        }
+        // Braces in quoted values don't open blocks.
+        let braces = line.split('"').step_by(2).collect::<String>();
+        depth += braces.matches('{').count();
+        depth -= braces.matches('}').count().min(depth);
+        if depth <= 1 {
+            tabs.extend(tab.take());
+        }
+    }
+    tabs
+}
+
+/// Parse the output of `list-clients`, skipping the header.
+pub fn parse_clients(output: &str) -> Vec<FocusedPane> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _client_id = fields.next()?;
+            let pane_id = fields.next()?.to_owned();
+            let command = fields.collect::<Vec<_>>().join(" ");
+            Some(FocusedPane { pane_id, command })
+        })
+        .collect()
+}
+
+impl Zellij {
+    pub fn new(session: String, tab: Option<String>, matcher: WindowMatcher) -> Zellij {
+        Zellij {
+            program: PathBuf::from("zellij"),
+            session,
+            tab,
+            matcher,
+        }
+    }
+
+    fn zellij(&self, args: &[&str]) -> Result<String> {
+        output(Command::new(&self.program).args(args))
+    }
+
+    fn action(&self, args: &[&str]) -> Result<String> {
+        output(
+            Command::new(&self.program)
+                .args(["--session", &self.session, "action"])
+                .args(args),
+        )
+    }
+
+    pub fn has_session(&self) -> bool {
+        // Fails when there are no sessions at all.
+        let sessions = self
+            .zellij(&["list-sessions", "--short"])
+            .unwrap_or_default();
+        sessions.lines().any(|line| line.trim() == self.session)
+    }
+
+    /// Pane focused by the first attached client.
+    pub fn focused_pane(&self) -> Result<FocusedPane> {
+        parse_clients(&self.action(&["list-clients"])?)
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                Error::Backend(format!(
+                    "no client attached to zellij session `{}`",
+                    self.session
+                ))
+            })
+    }
+
+    /// Tabs of the session with the commands running in them.
+    pub fn tabs(&self) -> Result<Vec<LayoutTab>> {
+        Ok(parse_layout(&self.action(&["dump-layout"])?))
+    }
+
+    fn is_match(&self, pane: &FocusedPane) -> bool {
+        self.matcher.is_match(pane.program(), &self.session)
+    }
+
+    /// Move focus through the panes of the current tab until one matches.
+    fn focus_in_tab(&self) -> Result<String> {
+        let first = self.focused_pane()?;
+        let mut pane = first.clone();
+        let mut moves = 0;
+        while moves < MAX_PANES {
+            if self.is_match(&pane) {
+                return Ok(format!(
+                    "zellij pane {} [{}] in {}",
+                    pane.pane_id,
+                    pane.program(),
+                    self.session
+                ));
+            }
+            self.action(&["focus-next-pane"])?;
+            moves += 1;
+            pane = self.focused_pane()?;
+            if pane.pane_id == first.pane_id {
+                return Err(Error::WindowNotFound);
+            }
+        }
+        // Gave up before cycling back to the first pane.
+        for _ in 0..moves {
+            self.action(&["focus-previous-pane"])?;
+        }
+        Err(Error::WindowNotFound)
+    }
+
+    fn write(&self, bytes: &[u8]) -> Result<()> {
+        let bytes: Vec<_> = bytes.iter().map(u8::to_string).collect();
+        let mut args = vec!["write"];
+        args.extend(bytes.iter().map(String::as_str));
+        self.action(&args)?;
+        Ok(())
+    }
+
+    fn write_chars(&self, chars: &str) -> Result<()> {
+        self.action(&["write-chars", chars])?;
+        Ok(())
+    }
+}
+
+impl Backend for Zellij {
+    fn focus(&mut self) -> Result<String> {
+        if !self.has_session() {
+            return Err(Error::WindowNotFound);
+        }
+        let Some(tab) = &self.tab else {
+            return self.focus_in_tab();
+        };
+        let previous_tab = self.tabs()?.into_iter().find(|tab| tab.focused);
+        self.action(&["go-to-tab-name", tab])?;
+        let res = self.focus_in_tab();
+        if let (Err(Error::WindowNotFound), Some(previous_tab)) = (&res, previous_tab) {
+            self.action(&["go-to-tab-name", &previous_tab.name])?;
+        }
+        res
+    }
+
+    fn is_open(&self) -> Result<bool> {
+        if !self.has_session() {
+            return Ok(false);
+        }
+        Ok(self
+            .tabs()?
+            .iter()
+            .filter(|tab| self.tab.iter().all(|name| tab.name == *name))
+            .flat_map(LayoutTab::programs)
+            .any(|program| self.matcher.is_match(program, &self.session)))
+    }
+
+    fn send(&mut self, steps: &[Step]) -> Result<()> {
+        for step in steps {
+            match step {
+                Step::Escape => self.write(b"\x1b")?,
+                Step::Command(command) => {
+                    self.write_chars(&format!(":{command}"))?;
+                    self.write(b"\r")?;
+                }
+                Step::Keys(keys) => self.write_chars(keys)?,
+            }
+        }
+        Ok(())
+    }
+}
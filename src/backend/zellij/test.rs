@@ -0,0 +1,226 @@
+use super::{parse_clients, parse_layout, FocusedPane, LayoutTab};
+
+#[test]
+fn test_parse_clients() {
+    let output = "CLIENT_ID ZELLIJ_PANE_ID RUNNING_COMMAND\n\
+                  1         terminal_2     /usr/bin/hx a.gd\n\
+                  2         plugin_0       N/A\n";
+    let panes = parse_clients(output);
+    assert_eq!(
+        panes[0],
+        FocusedPane {
+            pane_id: "terminal_2".to_owned(),
+            command: "/usr/bin/hx a.gd".to_owned(),
+        }
+    );
+    assert_eq!(panes[0].program(), "hx");
+    assert_eq!(panes[1].pane_id, "plugin_0");
+    assert!(parse_clients("CLIENT_ID ZELLIJ_PANE_ID RUNNING_COMMAND\n").is_empty());
+}
+
+/// Layout with the shell in the focused tab and Helix in tab `editor`.
+const LAYOUT: &str = r#"layout {
+    cwd "/home/user"
+    tab name="Tab #1" focus=true hide_floating_panes=true {
+        pane size=1 borderless=true {
+            plugin location="zellij:tab-bar"
+        }
+        pane
+        pane command="htop" name="{top}" {
+            start_suspended true
+        }
+    }
+    tab name="editor" {
+        pane command="/usr/bin/hx" cwd="game" focus=true {
+            args "a.gd"
+        }
+    }
+    new_tab_template {
+        pane command="vim"
+    }
+}
+"#;
+
+#[test]
+fn test_parse_layout() {
+    assert_eq!(
+        parse_layout(LAYOUT),
+        [
+            LayoutTab {
+                name: "Tab #1".to_owned(),
+                focused: true,
+                commands: vec!["htop".to_owned()],
+            },
+            LayoutTab {
+                name: "editor".to_owned(),
+                focused: false,
+                commands: vec!["/usr/bin/hx".to_owned()],
+            },
+        ]
+    );
+    assert_eq!(
+        parse_layout(LAYOUT)[1].programs().collect::<Vec<_>>(),
+        ["hx"]
+    );
+    assert!(parse_layout("").is_empty());
+}
+
+/// `zellij` replaced by a script with `panes` panes in session `game`, logging its arguments.
+/// The first three run zsh, hx and htop, the others sh. `dump-layout` prints [`LAYOUT`].
+#[cfg(unix)]
+fn fake_zellij(dir: &std::path::Path, panes: usize) -> std::path::PathBuf {
+    use crate::backend::test::fake_program;
+
+    let log = dir.join("log");
+    let focus = dir.join("focus");
+    std::fs::write(&focus, "0").unwrap();
+    let layout = dir.join("layout");
+    std::fs::write(&layout, LAYOUT).unwrap();
+    let script = format!(
+        r#"echo "$*" >> '{log}'
+case "$*" in
+  "list-sessions --short") echo other; echo game ;;
+  *"action list-clients")
+    echo "CLIENT_ID ZELLIJ_PANE_ID RUNNING_COMMAND"
+    n=$(cat '{focus}')
+    case $n in
+      0) echo "1 terminal_1 /bin/zsh" ;;
+      1) echo "1 terminal_2 /usr/bin/hx a.gd" ;;
+      2) echo "1 terminal_3 htop" ;;
+      *) echo "1 terminal_$((n + 1)) sh" ;;
+    esac ;;
+  *"action focus-next-pane") echo $(( ($(cat '{focus}') + 1) % {panes} )) > '{focus}' ;;
+  *"action focus-previous-pane") echo $(( ($(cat '{focus}') + {panes} - 1) % {panes} )) > '{focus}' ;;
+  *"action dump-layout") cat '{layout}' ;;
+esac
+"#,
+        log = log.display(),
+        focus = focus.display(),
+        layout = layout.display(),
+    );
+    fake_program(dir, "zellij", &script)
+}
+
+#[cfg(unix)]
+fn matcher(process_name: &str) -> crate::matcher::WindowMatcher {
+    use crate::matcher::{MatchMode, Pattern, WindowMatcher};
+
+    WindowMatcher {
+        process_name: Some(Pattern::new(process_name, MatchMode::Exact, true).unwrap()),
+        ..Default::default()
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_zellij_send() {
+    use super::Zellij;
+    use crate::{backend::Backend, error::Error, keyboard_macro::Step};
+
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("log");
+    let program = fake_zellij(dir.path(), 3);
+
+    let mut zellij = Zellij::new("game".to_owned(), None, matcher("hx"));
+    zellij.program = program;
+    assert_eq!(
+        zellij.focus().unwrap(),
+        "zellij pane terminal_2 [hx] in game"
+    );
+    zellij
+        .send(&[Step::Escape, Step::Command("o a.gd".to_owned())])
+        .unwrap();
+    let log_lines = std::fs::read_to_string(&log).unwrap();
+    let log_lines: Vec<_> = log_lines.lines().collect();
+    assert_eq!(
+        log_lines,
+        [
+            "list-sessions --short",
+            "--session game action list-clients",
+            "--session game action focus-next-pane",
+            "--session game action list-clients",
+            "--session game action write 27",
+            "--session game action write-chars :o a.gd",
+            "--session game action write 13",
+        ]
+    );
+
+    zellij.matcher = matcher("vim");
+    assert!(matches!(zellij.focus(), Err(Error::WindowNotFound)));
+    // Cycled through every pane back to the first.
+    assert_eq!(zellij.focused_pane().unwrap().pane_id, "terminal_2");
+    zellij.session = "missing".to_owned();
+    assert!(matches!(zellij.focus(), Err(Error::WindowNotFound)));
+}
+
+/// More panes than are tried, focus goes back to the pane focused before searching.
+#[cfg(unix)]
+#[test]
+fn test_zellij_restores_focus() {
+    use super::{Zellij, MAX_PANES};
+    use crate::{backend::Backend, error::Error};
+
+    let dir = tempfile::tempdir().unwrap();
+    let program = fake_zellij(dir.path(), MAX_PANES + 8);
+    std::fs::write(dir.path().join("focus"), "5").unwrap();
+
+    let mut zellij = Zellij::new("game".to_owned(), None, matcher("vim"));
+    zellij.program = program;
+    assert!(matches!(zellij.focus(), Err(Error::WindowNotFound)));
+    assert_eq!(zellij.focused_pane().unwrap().pane_id, "terminal_6");
+}
+
+/// Waiting for a pane looks at the layout, focus stays where it is.
+#[cfg(unix)]
+#[test]
+fn test_zellij_is_open() {
+    use super::Zellij;
+    use crate::backend::Backend;
+
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("log");
+    let program = fake_zellij(dir.path(), 3);
+
+    let mut zellij = Zellij::new("game".to_owned(), None, matcher("hx"));
+    zellij.program = program;
+    assert!(zellij.is_open().unwrap());
+    zellij.tab = Some("Tab #1".to_owned());
+    assert!(!zellij.is_open().unwrap());
+    zellij.tab = None;
+    zellij.matcher = matcher("vim");
+    assert!(!zellij.is_open().unwrap());
+    zellij.session = "missing".to_owned();
+    assert!(!zellij.is_open().unwrap());
+
+    let log = std::fs::read_to_string(&log).unwrap();
+    assert!(!log.contains("focus"));
+    assert_eq!(zellij.focused_pane().unwrap().pane_id, "terminal_1");
+}
+
+/// Going to the tab is undone when it has no matching pane.
+#[cfg(unix)]
+#[test]
+fn test_zellij_restores_tab() {
+    use super::Zellij;
+    use crate::{backend::Backend, error::Error};
+
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("log");
+    let program = fake_zellij(dir.path(), 3);
+
+    let mut zellij = Zellij::new("game".to_owned(), Some("editor".to_owned()), matcher("vim"));
+    zellij.program = program;
+    assert!(matches!(zellij.focus(), Err(Error::WindowNotFound)));
+    let log = std::fs::read_to_string(&log).unwrap();
+    let tab_switches: Vec<_> = log
+        .lines()
+        .filter(|line| line.contains("go-to-tab-name"))
+        .collect();
+    assert_eq!(
+        tab_switches,
+        [
+            "--session game action go-to-tab-name editor",
+            "--session game action go-to-tab-name Tab #1",
+        ]
+    );
+}
@@ -0,0 +1,325 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::{
+    backend::BackendKind,
+    command::{parse_env, SpawnMode},
+    keyboard_macro::parse_secs,
+    launcher::{LaunchMode, Launcher},
+    matcher::MatchMode,
+    path::PathStyle,
+    project::ProjectTag,
+    rank::Pick,
+    window_info::ListFormat,
+};
+
+/// Program to run Helix from external source.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// [DEPRECATED] Command to execute when no window found.
+    /// If not provided, only search will be done.
+    ///
+    /// DEPRECATED: use `run` flag.
+    #[arg(short = 'e', long, value_name = "PATH")]
+    pub execute_path: Option<String>,
+
+    /// [DEPRECATED] Time to wait after executing `execute_path`.
+    /// Does nothing if not execute.
+    ///
+    /// DEPRECATED: use `run_wait` flag.
+    #[arg(short = 'w', long, value_name = "SECS", value_parser = parse_secs)]
+    pub execute_wait: Option<f64>,
+
+    /// Search for window containing this string in the title.
+    /// Atleast one of `window_title` or `window_process_name` must provided.
+    #[arg(short = 't', long, value_name = "STRING")]
+    pub window_title: Option<String>,
+    /// Search for window containing this string in the process name.
+    /// Atleast one of `window_title` or `window_process_name` must provided.
+    #[arg(short = 'n', long, value_name = "STRING")]
+    pub window_process_name: Option<String>,
+    /// Skip windows with title matching this string.
+    #[arg(long, value_name = "STRING")]
+    pub exclude_title: Vec<String>,
+    /// Skip windows with process name matching this string.
+    #[arg(long, value_name = "STRING")]
+    pub exclude_process_name: Vec<String>,
+    /// How search strings are matched against window title and process name.
+    #[arg(long, value_enum, value_name = "MODE", default_value_t)]
+    pub match_mode: MatchMode,
+    /// Match search strings ignoring case.
+    #[arg(short = 'i', long)]
+    pub ignore_case: bool,
+    /// Which window to use when multiple windows match.
+    ///
+    /// `best` scores windows by: title containing the project directory name,
+    /// exact title, exact process name, preferred process names, then the most recently focused.
+    #[arg(long, value_enum, value_name = "POLICY", default_value_t)]
+    pub pick: Pick,
+    /// Process name to prefer when multiple windows match, can be given multiple times.
+    /// Added after `preferred_process_names` from the config.
+    #[arg(long, value_name = "NAME")]
+    pub prefer_process: Vec<String>,
+
+    /// Project path for helix to change directory to.
+    /// Will only be done when executed `execute_path`.
+    #[arg(short = 'p', long, value_name = "PATH")]
+    pub project_path: Option<String>,
+    /// File path for helix to open.
+    /// Godot's `res://` and `uid://` paths are resolved using the project containing `project.godot`.
+    /// `file://` URIs are accepted, including `#L12`, `#L12C5` and `#L12-L20` fragments.
+    #[arg(short = 'f', long, value_name = "PATH")]
+    pub file_path: Option<String>,
+    /// Line number in the file for helix to open.
+    #[arg(short = 'l', long, value_name = "NUM")]
+    pub line: Option<u32>,
+    /// Column number in the file for helix to open.
+    #[arg(short = 'c', long, value_name = "NUM")]
+    pub column: Option<u32>,
+    /// Number of the first line given by the caller, 0 like Godot or 1 like most other tools.
+    #[arg(
+        long,
+        value_name = "BASE",
+        default_value_t = 0,
+        value_parser = clap::value_parser!(u32).range(0..=1),
+    )]
+    pub line_base: u32,
+    /// Number of the first column given by the caller, 0 like Godot or 1 like most other tools.
+    #[arg(
+        long,
+        value_name = "BASE",
+        default_value_t = 0,
+        value_parser = clap::value_parser!(u32).range(0..=1),
+    )]
+    pub column_base: u32,
+
+    /// File for helix to open with its position in one argument.
+    /// Accepts `path:line:col`, `path:line`, `path(line,col)` and `file://` URIs.
+    /// Line and column use `line_base` and `column_base`.
+    #[arg(value_name = "LOCATION", conflicts_with = "file_path")]
+    pub location: Option<String>,
+
+    /// Option to reduce amount of time when writing full file path.
+    /// Only availiable when project path is provided.
+    #[arg(short = 'r', long)]
+    pub relative: bool,
+
+    /// Allow relative file path to go outside of the project path using `../`.
+    /// Only availiable with `relative`.
+    #[arg(long, requires = "relative")]
+    pub relative_parent: bool,
+
+    /// List availiable windows.
+    /// Format like so: [<process name>] <window title>
+    #[arg(long = "list")]
+    pub list_windows: bool,
+
+    /// Format of `list`.
+    /// Other than `plain`, the list includes process id, thread id, window handle
+    /// and whether the window matches the search.
+    #[arg(
+        long = "format",
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t,
+        requires = "list_windows"
+    )]
+    pub list_format: ListFormat,
+
+    /// Search through all windows.
+    /// Normally, windows in the config's ignore list are ignored,
+    /// by default those with title containing "Default IME" or "MSCTFIME UI";
+    /// add this flag to include them.
+    #[arg(long = "all")]
+    pub all: bool,
+
+    /// [DEPRECATED] Support for using Helix in WSL with application outside WSL
+    /// All it do currently is convert Windows path to Linux path
+    ///
+    /// DEPRECATED: use `path_style` flag.
+    #[arg(long)]
+    pub wsl: bool,
+
+    /// Path style of the environment Helix is running in.
+    /// Project and file path are converted to this style before being sent to Helix.
+    #[arg(long, value_enum, value_name = "STYLE", default_value_t)]
+    pub path_style: PathStyle,
+
+    /// Use clipboard instead of keyboard macros to speed up the process.
+    /// Assuming Ctrl+V works.
+    /// This will not replace your current clipboard!
+    #[arg(long)]
+    pub clipboard: bool,
+
+    /// Use no macro when a command is ran
+    #[arg(long)]
+    pub no_init_macro: bool,
+
+    /// Don't check that the Helix window is still focused before each macro step.
+    /// Normally, the macro is aborted when focus moved to another window.
+    #[arg(long)]
+    pub no_focus_check: bool,
+
+    /// Terminal to launch Helix in when no window is found.
+    /// Fills in `run`, `window_title` and `window_process_name` when they are not given.
+    ///
+    /// The new window is titled with `window_title`, default to "Helix",
+    /// followed by the project tag with `per_project`.
+    /// `windows-terminal`, `conhost` and `wsl` only work on Windows, `kitty` only elsewhere.
+    #[arg(long, value_enum, value_name = "TERMINAL")]
+    pub launcher: Option<Launcher>,
+
+    /// Helix program started by `launcher`, default to `helix_program` from the config or `hx`.
+    /// Also the default `window_process_name` of backends driving a terminal, like `tmux`.
+    #[arg(long, value_name = "PROGRAM")]
+    pub helix_program: Option<String>,
+
+    /// WSL distribution for `--launcher wsl`, default to the default distribution.
+    #[arg(long, value_name = "NAME", requires = "launcher")]
+    pub wsl_distro: Option<String>,
+
+    /// Command to run when no window is found.
+    /// If not provided, only search will be done.
+    ///
+    /// Placeholders are replaced with values after path conversion:
+    /// `{project}`, `{file}`, `{file_rel}` (relative to the project), `{line}`, `{col}` (1-based),
+    /// `{wsl_file}`, `{project_name}` and `{title_token}` (the `per_project` tags).
+    /// Use `{{` and `}}` for literal braces.
+    #[arg(long = "run", value_name = "CMD")]
+    pub run_command: Option<Vec<String>>,
+
+    /// How `run` command is started.
+    /// `direct` splits the command into arguments itself, grouping with `'...'` or `"..."`,
+    /// `shell` passes the command to `cmd /C`, or `sh -c` outside Windows.
+    /// Placeholder values are quoted for the shell, `cmd` refuses values containing `%`.
+    #[arg(long = "run-mode", value_enum, value_name = "MODE", default_value_t)]
+    pub run_command_mode: SpawnMode,
+
+    /// Working directory of `run` command.
+    #[arg(long = "run-cwd", value_name = "PATH")]
+    pub run_command_working_directory: Option<PathBuf>,
+
+    /// Environment variable for `run` command, can be given multiple times.
+    #[arg(long = "run-env", value_name = "KEY=VALUE", value_parser = parse_env)]
+    pub run_command_env: Vec<(String, String)>,
+
+    /// How the project and file are given to Helix launched by `run`.
+    /// `args` adds `-w <project> <file>:<line>:<col>` to the end of the command instead of typing macros,
+    /// so the command should end with `hx`. Windows found without launching always use macros.
+    #[arg(long, value_enum, value_name = "MODE", default_value_t)]
+    pub launch_mode: LaunchMode,
+
+    /// After running a command, wait for a bit before running a macro.
+    ///
+    /// `no-launch-macro` flag disable this entirely
+    #[arg(long = "run-wait", value_name = "SECS", value_parser = parse_secs)]
+    pub run_command_and_wait: Option<f64>,
+
+    /// After running a command, search for the window repeatedly until this many seconds passed
+    /// instead of searching once.
+    /// `run_wait` is still waited before the first search.
+    #[arg(long = "run-timeout", value_name = "SECS", value_parser = parse_secs)]
+    pub run_command_timeout: Option<f64>,
+
+    /// Time between searches for the window with `run_timeout`.
+    #[arg(
+        long = "run-poll-interval",
+        value_name = "SECS",
+        default_value_t = 0.1,
+        value_parser = parse_secs,
+    )]
+    pub run_command_poll_interval: f64,
+
+    /// With `run_timeout`, wait for a bit after the window first appeared
+    /// to let Helix start before running a macro.
+    #[arg(
+        long = "run-ready-wait",
+        value_name = "SECS",
+        default_value_t = 0.0,
+        value_parser = parse_secs,
+    )]
+    pub run_command_ready_wait: f64,
+
+    /// Use one Helix window per project.
+    /// Only windows with the project tag in the title are used,
+    /// and `run` is used to launch a new one for the project when there is none.
+    ///
+    /// `name` is the project directory name, `token` is generated from the project path
+    /// (shown with `verbose`) and should be put in the window title by `run`.
+    #[arg(long, value_enum, value_name = "TAG", requires = "project_path")]
+    pub per_project: Option<ProjectTag>,
+
+    /// Don't read or write the state file of instances launched by `run`.
+    /// Normally, windows launched for the project are preferred on later runs.
+    #[arg(long)]
+    pub no_state: bool,
+
+    /// Where Helix runs.
+    /// `tmux` searches tmux panes instead of windows and sends macros with `tmux send-keys`,
+    /// `window_process_name` is matched against the command running in the pane, default to "hx",
+    /// and `window_title` against `session:window`.
+    /// `wezterm` uses `wezterm cli`, matching `window_process_name` against the pane title
+    /// and `window_title` against the window title.
+    /// `kitty` uses kitty's remote control, matching `window_process_name` against the program
+    /// running in the window and `window_title` against the window title.
+    /// `zellij` uses `zellij action` on `zellij_session`, matching `window_process_name` against the program
+    /// running in the pane and `window_title` against the session name, as zellij doesn't report pane titles.
+    /// `x11` searches windows of the X11 desktop in `DISPLAY` and types macros with XTest,
+    /// matching, ranking, ignoring and listing windows like on Windows.
+    #[arg(long, value_enum, value_name = "BACKEND", default_value_t)]
+    pub backend: BackendKind,
+
+    /// Socket name of the tmux server, like `tmux -L`, or socket path like `tmux -S`.
+    #[arg(long, value_name = "NAME")]
+    pub tmux_socket: Option<String>,
+
+    /// Address kitty listens on, like `unix:/tmp/kitty` given to `kitty --listen-on`.
+    /// Default to the `KITTY_LISTEN_ON` environment variable.
+    #[arg(long, value_name = "ADDRESS")]
+    pub kitty_listen_on: Option<String>,
+
+    /// zellij session Helix runs in.
+    /// Default to the `ZELLIJ_SESSION_NAME` environment variable.
+    #[arg(long, value_name = "NAME")]
+    pub zellij_session: Option<String>,
+
+    /// zellij tab to go to before searching for the Helix pane.
+    #[arg(long, value_name = "NAME")]
+    pub zellij_tab: Option<String>,
+
+    /// Print more information about what is happening.
+    #[arg(short = 'v', long)]
+    pub verbose: bool,
+
+    /// Show the config file and effective config, then exit.
+    #[arg(long)]
+    pub doctor: bool,
+
+    /// Config file to use.
+    /// Default to `helix-win-runner\config.toml` in the user's config directory.
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Run as a daemon, running requests sent with `use_daemon` one at a time
+    /// so their macros never interleave.
+    /// Config is only read once, restart the daemon after changing it.
+    /// State file and windows are read again for each request.
+    #[arg(long)]
+    pub daemon: bool,
+
+    /// Send the arguments to the daemon and wait until it ran them, instead of running directly.
+    /// Runs directly when no daemon is listening, and with `doctor`, `list` or `pick ask`.
+    /// The daemon refuses requests giving `config`, it uses its own.
+    #[arg(long, conflicts_with = "daemon")]
+    pub use_daemon: bool,
+
+    /// Where the daemon listens, a Unix socket path or a named pipe on Windows.
+    /// Default to `\\.\pipe\helix-win-runner` on Windows
+    /// and `helix-win-runner.sock` in the runtime directory elsewhere,
+    /// or in `helix-win-runner-<uid>` in the temporary directory without one.
+    /// Sockets of other users are refused.
+    #[arg(long, value_name = "ADDRESS")]
+    pub daemon_address: Option<String>,
+}
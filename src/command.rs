@@ -0,0 +1,257 @@
+use std::{
+    borrow::Cow,
+    path::PathBuf,
+    process::{Child, Command},
+};
+
+use crate::error::{Error, Result};
+
+#[cfg(test)]
+mod test;
+
+/// How the `run` command is started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SpawnMode {
+    /// Split the command into program and arguments and start it directly.
+    #[default]
+    Direct,
+    /// Pass the command to `cmd /C` on Windows and `sh -c` elsewhere, using their parsing rules.
+    Shell,
+}
+
+/// Program and argument to run a command line with in `Shell` mode.
+#[cfg(windows)]
+const SHELL: [&str; 2] = ["cmd", "/C"];
+#[cfg(not(windows))]
+const SHELL: [&str; 2] = ["sh", "-c"];
+
+/// Command to launch Helix with.
+#[derive(Debug, Clone, Default)]
+pub struct RunCommand {
+    /// Command given by the user. Split into arguments in `Direct` mode.
+    pub command: Vec<String>,
+    pub mode: SpawnMode,
+    pub working_directory: Option<PathBuf>,
+    pub env: Vec<(String, String)>,
+    /// Values of `{name}` placeholders in the command.
+    pub placeholders: Vec<(&'static str, String)>,
+    /// Arguments added after the command as is, without splitting or placeholders.
+    pub args: Vec<String>,
+    /// `command` is already program and arguments, used as is like `args`.
+    /// Set for the deprecated `execute_path`, which is a program path that may contain spaces.
+    pub verbatim: bool,
+}
+
+impl RunCommand {
+    /// Program and arguments to be spawned.
+    pub fn program_args(&self) -> Result<Vec<String>> {
+        match self.mode {
+            SpawnMode::Direct if self.verbatim => {
+                let args: Vec<_> = self.command.iter().chain(&self.args).cloned().collect();
+                if args.is_empty() {
+                    return Err(Error::InvalidCommand("command is empty".to_owned()));
+                }
+                Ok(args)
+            }
+            SpawnMode::Direct => {
+                // Placeholders are expanded after splitting so values don't need escaping.
+                let mut args = vec![];
+                for command in &self.command {
+                    for arg in split_command(command)? {
+                        args.push(expand_placeholders(&arg, &self.placeholders, |value| {
+                            Ok(Cow::from(value))
+                        })?);
+                    }
+                }
+                if args.is_empty() {
+                    return Err(Error::InvalidCommand("command is empty".to_owned()));
+                }
+                args.extend(self.args.iter().cloned());
+                Ok(args)
+            }
+            SpawnMode::Shell => {
+                let command_line = self
+                    .command
+                    .iter()
+                    .map(|command| match self.verbatim {
+                        true => shell_quote(command).map(Cow::into_owned),
+                        false => expand_placeholders(command, &self.placeholders, shell_quote),
+                    })
+                    .chain(
+                        self.args
+                            .iter()
+                            .map(|arg| shell_quote(arg).map(Cow::into_owned)),
+                    )
+                    .collect::<Result<Vec<_>>>()?
+                    .join(" ");
+                Ok(vec![SHELL[0].to_owned(), SHELL[1].to_owned(), command_line])
+            }
+        }
+    }
+
+    pub fn spawn(&self) -> Result<Child> {
+        let args = self.program_args()?;
+        let mut command = Command::new(&args[0]);
+        match self.mode {
+            // The command line is given to cmd as is, quoting it would change its meaning.
+            #[cfg(windows)]
+            SpawnMode::Shell => {
+                use std::os::windows::process::CommandExt;
+                command.arg(&args[1]).raw_arg(&args[2]);
+            }
+            _ => {
+                command.args(&args[1..]);
+            }
+        }
+        if let Some(dir) = &self.working_directory {
+            command.current_dir(dir);
+        }
+        command.envs(self.env.iter().map(|(key, value)| (key, value)));
+        Ok(command.spawn()?)
+    }
+}
+
+/// Replace `{name}` with the value of `name` in `placeholders`, escaped with `escape`.
+/// `{{` and `}}` are literal braces, unknown placeholders are kept as is.
+pub fn expand_placeholders<'a>(
+    template: &str,
+    placeholders: &'a [(&str, String)],
+    escape: impl Fn(&'a str) -> Result<Cow<'a, str>>,
+) -> Result<String> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        expanded.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            expanded.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        let value = rest.strip_prefix('{').and_then(|after| {
+            let (name, after) = after.split_once('}')?;
+            let (_, value) = placeholders.iter().find(|(key, _)| *key == name)?;
+            Some((value, after))
+        });
+        match value {
+            Some((value, after)) => {
+                expanded.push_str(&escape(value)?);
+                rest = after;
+            }
+            None => {
+                expanded.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Quote `arg` for `cmd` if it contains spaces or characters special to it.
+///
+/// Fails when `arg` contains `%`, which `cmd` expands as `%VAR%` even in quotes
+/// and has no escape for on its command line.
+#[cfg(windows)]
+pub fn shell_quote(arg: &str) -> Result<Cow<'_, str>> {
+    const SPECIAL: &[char] = &[
+        ' ', '\t', '&', '|', '<', '>', '^', '(', ')', '!', ',', ';', '=',
+    ];
+    if arg.contains('%') {
+        Err(Error::InvalidCommand(format!(
+            "`{arg}` contains `%` which `cmd` would expand, use the `direct` run mode"
+        )))
+    } else if arg.is_empty() {
+        Ok(Cow::Borrowed("\"\""))
+    } else if arg.contains(SPECIAL) || arg.contains('"') {
+        Ok(Cow::Owned(format!("\"{}\"", arg.replace('"', "\"\""))))
+    } else {
+        Ok(Cow::Borrowed(arg))
+    }
+}
+
+/// Quote `arg` for `sh` unless it only has characters that are never special to it.
+/// Never fails, any value can be quoted for `sh`.
+#[cfg(not(windows))]
+pub fn shell_quote(arg: &str) -> Result<Cow<'_, str>> {
+    let is_plain = |c: char| c.is_ascii_alphanumeric() || "_-./:@%+,=".contains(c);
+    if arg.is_empty() {
+        Ok(Cow::Borrowed("''"))
+    } else if arg.chars().all(is_plain) {
+        Ok(Cow::Borrowed(arg))
+    } else {
+        // Nothing is special in single quotes, a quote is closed, escaped, then reopened.
+        Ok(Cow::Owned(format!("'{}'", arg.replace('\'', r"'\''"))))
+    }
+}
+
+/// Quote `arg` so [`split_command`] gives it back as one argument.
+pub fn quote_arg(arg: &str) -> Cow<'_, str> {
+    if arg.is_empty() {
+        Cow::Borrowed("''")
+    } else if !arg.contains(|c: char| c.is_whitespace() || c == '\'' || c == '"') {
+        Cow::Borrowed(arg)
+    } else {
+        // A single quote is closed, added in double quotes, then reopened.
+        Cow::Owned(format!("'{}'", arg.replace('\'', r#"'"'"'"#)))
+    }
+}
+
+/// Parse `KEY=VALUE`.
+pub fn parse_env(s: &str) -> std::result::Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_owned(), value.to_owned())),
+        _ => Err(format!("expected `KEY=VALUE`, got `{s}`")),
+    }
+}
+
+/// Split a command line into arguments.
+///
+/// Arguments are separated by whitespace and can be grouped by `'...'` or `"..."`.
+/// Single quotes keep everything as is, in double quotes `\"` is a quote.
+/// Backslashes are kept elsewhere so Windows paths need no escaping.
+pub fn split_command(command: &str) -> Result<Vec<String>> {
+    let mut args = vec![];
+    let mut current: Option<String> = None;
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if let Some(arg) = current.take() {
+                    args.push(arg);
+                }
+            }
+            '\'' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => arg.push(c),
+                        None => return Err(unclosed_quote(command)),
+                    }
+                }
+            }
+            '"' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') if chars.peek() == Some(&'"') => {
+                            arg.push('"');
+                            chars.next();
+                        }
+                        Some(c) => arg.push(c),
+                        None => return Err(unclosed_quote(command)),
+                    }
+                }
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+    Ok(args)
+}
+
+fn unclosed_quote(command: &str) -> Error {
+    Error::InvalidCommand(format!("unclosed quote in `{command}`"))
+}
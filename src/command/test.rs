@@ -0,0 +1,206 @@
+use std::borrow::Cow;
+
+use super::{
+    expand_placeholders, parse_env, quote_arg, shell_quote, split_command, RunCommand, SpawnMode,
+    SHELL,
+};
+
+#[test]
+fn test_split_command() {
+    let split = |s: &str| split_command(s).unwrap();
+    assert_eq!(
+        split("wt nt --title 'Helix' hx -w C:\\Users\\a\\project"),
+        [
+            "wt",
+            "nt",
+            "--title",
+            "Helix",
+            "hx",
+            "-w",
+            "C:\\Users\\a\\project"
+        ]
+    );
+    assert_eq!(
+        split(r#"hx "C:\My Files\a&b^c%d%.gd""#),
+        ["hx", r"C:\My Files\a&b^c%d%.gd"]
+    );
+    assert_eq!(
+        split(r#"echo "say \"hi\"" it's'"#),
+        ["echo", r#"say "hi""#, "its"]
+    );
+    assert_eq!(split(r#"a""  ''"#), ["a", ""]);
+    assert_eq!(split("  "), Vec::<String>::new());
+    assert!(split_command("hx 'a").is_err());
+    assert!(split_command("hx \"a").is_err());
+}
+
+#[test]
+fn test_program_args() {
+    let mut command = RunCommand {
+        command: vec!["hx -w 'C:/my project'".to_owned(), "a.gd".to_owned()],
+        ..Default::default()
+    };
+    assert_eq!(
+        command.program_args().unwrap(),
+        ["hx", "-w", "C:/my project", "a.gd"]
+    );
+
+    command.mode = SpawnMode::Shell;
+    assert_eq!(
+        command.program_args().unwrap(),
+        [SHELL[0], SHELL[1], "hx -w 'C:/my project' a.gd"]
+    );
+
+    command.mode = SpawnMode::Direct;
+    command.command = vec![" ".to_owned()];
+    assert!(command.program_args().is_err());
+}
+
+#[test]
+fn test_parse_env() {
+    assert_eq!(parse_env("A=b=c"), Ok(("A".to_owned(), "b=c".to_owned())));
+    assert_eq!(parse_env("A="), Ok(("A".to_owned(), "".to_owned())));
+    assert!(parse_env("=a").is_err());
+    assert!(parse_env("a").is_err());
+}
+
+#[test]
+fn test_expand_placeholders() {
+    let placeholders = [
+        ("file", "C:/my game/a&b.gd".to_owned()),
+        ("line", "12".to_owned()),
+    ];
+    let expand =
+        |s: &str| expand_placeholders(s, &placeholders, |value| Ok(Cow::from(value))).unwrap();
+    assert_eq!(expand("{file}:{line}"), "C:/my game/a&b.gd:12");
+    assert_eq!(expand("{{file}} {unknown} {line"), "{file} {unknown} {line");
+    assert_eq!(expand("}}{}"), "}{}");
+
+    assert_eq!(
+        expand_placeholders("hx {file}:{line}", &placeholders, shell_quote).unwrap(),
+        format!("hx {}:12", shell_quote("C:/my game/a&b.gd").unwrap())
+    );
+}
+
+#[test]
+fn test_placeholders_in_command() {
+    let mut command = RunCommand {
+        command: vec!["hx -w {project} '{file}:{line}'".to_owned()],
+        placeholders: vec![
+            ("project", "C:/my game".to_owned()),
+            ("file", "a b.gd".to_owned()),
+            ("line", "3".to_owned()),
+        ],
+        ..Default::default()
+    };
+    assert_eq!(
+        command.program_args().unwrap(),
+        ["hx", "-w", "C:/my game", "a b.gd:3"]
+    );
+
+    command.mode = SpawnMode::Shell;
+    command.command = vec!["hx -w {project} {file}:{line}".to_owned()];
+    assert_eq!(
+        command.program_args().unwrap()[2],
+        format!(
+            "hx -w {} {}:3",
+            shell_quote("C:/my game").unwrap(),
+            shell_quote("a b.gd").unwrap()
+        )
+    );
+}
+
+#[test]
+fn test_extra_args() {
+    let mut command = RunCommand {
+        command: vec!["wt nt hx".to_owned()],
+        args: vec!["-w".to_owned(), "C:/my {game}".to_owned()],
+        ..Default::default()
+    };
+    assert_eq!(
+        command.program_args().unwrap(),
+        ["wt", "nt", "hx", "-w", "C:/my {game}"]
+    );
+
+    command.mode = SpawnMode::Shell;
+    assert_eq!(
+        command.program_args().unwrap()[2],
+        format!("wt nt hx -w {}", shell_quote("C:/my {game}").unwrap())
+    );
+}
+
+#[test]
+fn test_verbatim() {
+    let program = r"C:\Program Files\Alacritty\it's {file}.exe";
+    let command = RunCommand {
+        command: vec![program.to_owned()],
+        placeholders: vec![("file", "a.gd".to_owned())],
+        verbatim: true,
+        ..Default::default()
+    };
+    assert_eq!(command.program_args().unwrap(), [program]);
+}
+
+#[cfg(windows)]
+#[test]
+fn test_shell_quote() {
+    assert_eq!(shell_quote("a.gd").unwrap(), "a.gd");
+    assert_eq!(shell_quote("").unwrap(), r#""""#);
+    assert_eq!(shell_quote("a b").unwrap(), r#""a b""#);
+    assert_eq!(shell_quote(r#"say "hi""#).unwrap(), r#""say ""hi""""#);
+}
+
+/// `cmd` would replace `%PATH%` even in quotes, so the command is refused instead.
+#[cfg(windows)]
+#[test]
+fn test_shell_percent() {
+    let file = "C:/game/a%PATH%b.gd";
+    assert!(shell_quote(file).is_err());
+    let mut command = RunCommand {
+        command: vec!["hx {file}".to_owned()],
+        placeholders: vec![("file", file.to_owned())],
+        mode: SpawnMode::Shell,
+        ..Default::default()
+    };
+    assert!(matches!(
+        command.program_args(),
+        Err(crate::error::Error::InvalidCommand(_))
+    ));
+    command.mode = SpawnMode::Direct;
+    assert_eq!(command.program_args().unwrap(), ["hx", file]);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_shell_quote() {
+    assert_eq!(shell_quote("a.gd").unwrap(), "a.gd");
+    assert_eq!(shell_quote("").unwrap(), "''");
+    assert_eq!(shell_quote("a b").unwrap(), "'a b'");
+    assert_eq!(shell_quote("$(rm -rf ~).gd").unwrap(), "'$(rm -rf ~).gd'");
+    assert_eq!(shell_quote("`id`.gd").unwrap(), "'`id`.gd'");
+    assert_eq!(shell_quote("it's").unwrap(), r"'it'\''s'");
+
+    // `sh` gives every value back as is.
+    for arg in [
+        "$(echo no).gd",
+        "`echo no`",
+        "it's \"$HOME\"",
+        "a\\b",
+        "~",
+        "*",
+    ] {
+        let output = std::process::Command::new("sh")
+            .args(["-c", &format!("printf %s {}", shell_quote(arg).unwrap())])
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), arg);
+    }
+}
+
+#[test]
+fn test_quote_arg() {
+    for arg in ["hx", "", "Helix - game", "it's", r#"say "hi""#, "a' b\\"] {
+        assert_eq!(split_command(&quote_arg(arg)).unwrap(), [arg]);
+    }
+    assert_eq!(quote_arg("hx"), "hx");
+}
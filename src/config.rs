@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    error::{Error, Result},
+    path::PathMapping,
+    window_info::IgnoreList,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Settings read from the config file.
+///
+/// Default location is `<config dir>/helix-win-runner/config.toml`,
+/// which is `%APPDATA%\helix-win-runner\config.toml` on Windows.
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Rules applied to project and file path after path style conversion.
+    pub path_map: Vec<PathMapping>,
+    /// Process names preferred when multiple windows match, in order of preference.
+    pub preferred_process_names: Vec<String>,
+    /// Windows to skip, unless `all` flag is given.
+    pub ignore: IgnoreList,
+    /// Helix program started by `launcher` presets, default to `hx`.
+    pub helix_program: Option<String>,
+}
+
+impl Config {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("helix-win-runner").join("config.toml"))
+    }
+
+    /// Load config from `path`, or from the default location if not provided.
+    /// Missing file at the default location gives the default config.
+    pub fn load(path: Option<&Path>) -> Result<Config> {
+        let (path, required) = match path {
+            Some(path) => (path.to_owned(), true),
+            None => match Config::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Config::default())
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Config::parse(&content).map_err(|message| Error::Config { path, message })
+    }
+
+    /// Config as TOML, used to show the effective config.
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("config to serialize")
+    }
+
+    pub fn parse(content: &str) -> std::result::Result<Config, String> {
+        toml::from_str(content).map_err(|e| e.to_string())
+    }
+}
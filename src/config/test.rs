@@ -0,0 +1,54 @@
+use super::Config;
+use crate::{path::PathMapping, window_info::IgnoreList};
+
+#[test]
+fn test_parse_config() {
+    let config = Config::parse(
+        r#"
+        [[path_map]]
+        from = 'D:\work'
+        to = "/workspace"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.path_map,
+        vec![PathMapping {
+            from: "D:\\work".to_owned(),
+            to: "/workspace".to_owned(),
+        }]
+    );
+
+    assert!(Config::parse("").unwrap().path_map.is_empty());
+    assert!(Config::parse("unknown = 1").is_err());
+}
+
+#[test]
+fn test_parse_ignore_list() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config.ignore, IgnoreList::default());
+
+    let config = Config::parse(
+        r#"
+        [ignore]
+        titles = []
+        invisible = true
+        "#,
+    )
+    .unwrap();
+    assert!(config.ignore.titles.is_empty());
+    assert!(config.ignore.invisible);
+    assert!(!config.ignore.tool_windows);
+}
+
+#[test]
+fn test_config_to_toml() {
+    let mut config = Config::default();
+    config.path_map.push(PathMapping {
+        from: "D:\\work".to_owned(),
+        to: "/workspace".to_owned(),
+    });
+    let parsed = Config::parse(&config.to_toml()).unwrap();
+    assert_eq!(parsed.path_map, config.path_map);
+    assert_eq!(parsed.ignore, config.ignore);
+}
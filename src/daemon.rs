@@ -0,0 +1,411 @@
+use std::{
+    io::{BufRead, BufReader, ErrorKind, Read, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::{Error, Result};
+
+#[cfg(test)]
+mod test;
+
+/// Request sent by a client, run by the daemon as if the runner was invoked with `args`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Request {
+    /// Command line arguments, without the program name.
+    pub args: Vec<String>,
+    /// Working directory of the client, relative paths in `args` are relative to it.
+    pub working_directory: Option<PathBuf>,
+}
+
+/// Answer to a request, sent once it was run.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Response {
+    /// Error message when the request failed.
+    pub error: Option<String>,
+    /// What the run printed, for the client to print.
+    pub stdout: String,
+    /// Warnings of the run, for the client to print to standard error.
+    pub stderr: String,
+}
+
+/// How long a client may take to send its request by default.
+pub const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default daemon address, a named pipe on Windows
+/// and `helix-win-runner.sock` in the runtime directory elsewhere.
+/// Without a runtime directory, the socket goes in a directory of the user in the temporary directory,
+/// which other users can't create the socket in first.
+#[cfg(unix)]
+pub fn default_address() -> String {
+    let dir = dirs::runtime_dir()
+        .unwrap_or_else(|| std::env::temp_dir().join(format!("helix-win-runner-{}", uid())));
+    dir.join("helix-win-runner.sock").display().to_string()
+}
+
+#[cfg(not(unix))]
+pub fn default_address() -> String {
+    r"\\.\pipe\helix-win-runner".to_owned()
+}
+
+#[cfg(unix)]
+fn uid() -> u32 {
+    // SAFETY: Always succeeds.
+    unsafe { libc::getuid() }
+}
+
+/// Fail when `path` belongs to another user, who could read requests sent to it or answer them.
+#[cfg(unix)]
+fn check_owner(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    if std::fs::symlink_metadata(path)?.uid() != uid() {
+        return Err(Error::Daemon(format!(
+            "`{}` belongs to another user",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Whether sending to the daemon failed because none is listening.
+pub fn is_not_running(error: &Error) -> bool {
+    matches!(error, Error::Io(e) if matches!(e.kind(), ErrorKind::NotFound | ErrorKind::ConnectionRefused))
+}
+
+/// Messages are JSON, one per line.
+fn write_message(stream: &mut impl Write, message: &impl Serialize) -> Result<()> {
+    let mut line = serde_json::to_vec(message).map_err(|e| Error::Daemon(e.to_string()))?;
+    line.push(b'\n');
+    stream.write_all(&line)?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// `None` when the other side closed without sending anything.
+fn read_message<T: DeserializeOwned>(stream: &mut impl Read) -> Result<Option<T>> {
+    let mut line = String::new();
+    if BufReader::new(stream).read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| Error::Daemon(format!("invalid message: {e}")))
+}
+
+/// Send `request` to the daemon at `address` and wait until it was run.
+pub fn send(address: &str, request: &Request) -> Result<Response> {
+    let mut stream = connect(address)?;
+    write_message(&mut stream, request)?;
+    read_message(&mut stream)?.ok_or_else(|| Error::Daemon("closed without answering".to_owned()))
+}
+
+#[cfg(unix)]
+fn connect(address: &str) -> Result<std::os::unix::net::UnixStream> {
+    check_owner(std::path::Path::new(address))?;
+    Ok(std::os::unix::net::UnixStream::connect(address)?)
+}
+
+#[cfg(windows)]
+fn connect(address: &str) -> Result<std::fs::File> {
+    use std::time::{Duration, Instant};
+
+    const ERROR_PIPE_BUSY: i32 = 231;
+    // Busy while other clients are queued, the daemon makes a new pipe instance for each.
+    let start = Instant::now();
+    loop {
+        match std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(address)
+        {
+            Err(e)
+                if e.raw_os_error() == Some(ERROR_PIPE_BUSY)
+                    && start.elapsed() < Duration::from_secs(60) =>
+            {
+                std::thread::sleep(Duration::from_millis(50))
+            }
+            res => return Ok(res?),
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn connect(_address: &str) -> Result<std::fs::File> {
+    Err(Error::Daemon("not supported on this platform".to_owned()))
+}
+
+/// Daemon listening for requests.
+///
+/// Connections are accepted one at a time, so requests arriving together
+/// wait in the queue instead of running at the same time.
+/// Windows and state file are still read again for every request, only the config is kept.
+pub struct Daemon {
+    /// How long a client may take to send its request, so one that sends nothing can't block the queue.
+    pub read_timeout: Duration,
+    #[cfg(unix)]
+    listener: std::os::unix::net::UnixListener,
+    #[cfg(all(windows, feature = "windows"))]
+    pipe: pipe::PipeListener,
+}
+
+impl Daemon {
+    /// Listen on the Unix socket at `address`, replacing a stale one left by a daemon that died.
+    ///
+    /// A missing directory is created for the user only,
+    /// and only the user can connect to the socket.
+    #[cfg(unix)]
+    pub fn bind(address: &str) -> Result<Daemon> {
+        use std::os::unix::{
+            fs::{DirBuilderExt, PermissionsExt},
+            net::{UnixListener, UnixStream},
+        };
+
+        let path = std::path::Path::new(address);
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            if !dir.exists() {
+                std::fs::DirBuilder::new().mode(0o700).create(dir)?;
+            }
+        }
+        if path.symlink_metadata().is_ok() {
+            check_owner(path)?;
+            if UnixStream::connect(address).is_ok() {
+                return Err(Error::Daemon(format!("already running on `{address}`")));
+            }
+            std::fs::remove_file(address)?;
+        }
+        let listener = UnixListener::bind(address)?;
+        std::fs::set_permissions(address, std::fs::Permissions::from_mode(0o600))?;
+        Ok(Daemon {
+            read_timeout: READ_TIMEOUT,
+            listener,
+        })
+    }
+
+    /// Listen on the named pipe `address`.
+    #[cfg(all(windows, feature = "windows"))]
+    pub fn bind(address: &str) -> Result<Daemon> {
+        Ok(Daemon {
+            read_timeout: READ_TIMEOUT,
+            pipe: pipe::PipeListener::bind(address)?,
+        })
+    }
+
+    #[cfg(not(any(unix, all(windows, feature = "windows"))))]
+    pub fn bind(_address: &str) -> Result<Daemon> {
+        Err(Error::Daemon("not supported in this build".to_owned()))
+    }
+
+    /// Wait for the next client.
+    /// Fails only when the daemon can't accept clients anymore.
+    #[cfg(unix)]
+    pub fn accept(&mut self) -> Result<Client> {
+        Ok(Client {
+            stream: self.listener.accept()?.0,
+            read_timeout: self.read_timeout,
+        })
+    }
+
+    #[cfg(all(windows, feature = "windows"))]
+    pub fn accept(&mut self) -> Result<Client> {
+        Ok(Client {
+            stream: self.pipe.accept()?,
+            read_timeout: self.read_timeout,
+        })
+    }
+
+    #[cfg(not(any(unix, all(windows, feature = "windows"))))]
+    pub fn accept(&mut self) -> Result<Client> {
+        Err(Error::Daemon("not supported in this build".to_owned()))
+    }
+}
+
+#[cfg(unix)]
+type Stream = std::os::unix::net::UnixStream;
+#[cfg(all(windows, feature = "windows"))]
+type Stream = pipe::Pipe;
+#[cfg(not(any(unix, all(windows, feature = "windows"))))]
+type Stream = std::fs::File;
+
+/// Client connected to the daemon.
+pub struct Client {
+    stream: Stream,
+    read_timeout: Duration,
+}
+
+impl Client {
+    /// Read the request, run it with `handler` and answer it.
+    pub fn serve(mut self, handler: impl FnOnce(Request) -> Response) -> Result<()> {
+        #[cfg(any(unix, all(windows, feature = "windows")))]
+        self.stream.set_read_timeout(Some(self.read_timeout))?;
+        let request = read_message(&mut self.stream).map_err(|e| match e {
+            Error::Io(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                Error::Daemon(format!(
+                    "no request received within {} seconds",
+                    self.read_timeout.as_secs_f64()
+                ))
+            }
+            e => e,
+        })?;
+        // Nothing is sent when checking whether a daemon is running.
+        let Some(request) = request else {
+            return Ok(());
+        };
+        let response = handler(request);
+        write_message(&mut self.stream, &response)
+    }
+}
+
+#[cfg(all(windows, feature = "windows"))]
+mod pipe {
+    use std::{
+        fs::File,
+        io::{self, Read, Write},
+        os::windows::{ffi::OsStrExt, io::AsRawHandle, io::FromRawHandle},
+        ptr,
+        time::{Duration, Instant},
+    };
+    use winapi::{
+        shared::winerror::ERROR_PIPE_CONNECTED,
+        um::{fileapi, handleapi::INVALID_HANDLE_VALUE, namedpipeapi, winbase},
+    };
+
+    use crate::error::{Error, Result};
+
+    /// Named pipe server, always keeping an instance waiting for the next client.
+    pub struct PipeListener {
+        name: Vec<u16>,
+        next: File,
+    }
+
+    /// Connected pipe instance, flushing waits until the client read everything written.
+    pub struct Pipe {
+        file: File,
+        read_timeout: Option<Duration>,
+    }
+
+    fn create(name: &[u16], first: bool) -> io::Result<File> {
+        let mut open_mode = winbase::PIPE_ACCESS_DUPLEX;
+        if first {
+            // Fails when the pipe already exists, meaning a daemon is running.
+            open_mode |= winbase::FILE_FLAG_FIRST_PIPE_INSTANCE;
+        }
+        // SAFETY: `name` is null terminated.
+        let handle = unsafe {
+            namedpipeapi::CreateNamedPipeW(
+                name.as_ptr(),
+                open_mode,
+                winbase::PIPE_TYPE_BYTE
+                    | winbase::PIPE_READMODE_BYTE
+                    | winbase::PIPE_WAIT
+                    | winbase::PIPE_REJECT_REMOTE_CLIENTS,
+                winbase::PIPE_UNLIMITED_INSTANCES,
+                4096,
+                4096,
+                0,
+                ptr::null_mut(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: Handle is valid and owned by nothing else.
+        Ok(unsafe { File::from_raw_handle(handle as _) })
+    }
+
+    impl PipeListener {
+        pub fn bind(address: &str) -> Result<PipeListener> {
+            let name: Vec<u16> = std::ffi::OsStr::new(address)
+                .encode_wide()
+                .chain([0])
+                .collect();
+            let next = create(&name, true).map_err(|e| match e.kind() {
+                io::ErrorKind::PermissionDenied => {
+                    Error::Daemon(format!("already running on `{address}`"))
+                }
+                _ => Error::Io(e),
+            })?;
+            Ok(PipeListener { name, next })
+        }
+
+        pub fn accept(&mut self) -> Result<Pipe> {
+            // SAFETY: Handle is a valid pipe instance.
+            let connected = unsafe {
+                namedpipeapi::ConnectNamedPipe(self.next.as_raw_handle() as _, ptr::null_mut())
+            };
+            if connected == 0 {
+                let e = io::Error::last_os_error();
+                // The client connected before `ConnectNamedPipe`.
+                if e.raw_os_error() != Some(ERROR_PIPE_CONNECTED as i32) {
+                    return Err(Error::Io(e));
+                }
+            }
+            // Clients arriving while this one runs connect to the new instance and wait.
+            let next = create(&self.name, false)?;
+            Ok(Pipe {
+                file: std::mem::replace(&mut self.next, next),
+                read_timeout: None,
+            })
+        }
+    }
+
+    impl Pipe {
+        /// Like a socket read timeout, reads fail with `TimedOut` when nothing arrived in time.
+        pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+            self.read_timeout = timeout;
+            Ok(())
+        }
+
+        /// Wait until there is something to read, or the client is gone and reading tells so.
+        fn wait_readable(&self, timeout: Duration) -> io::Result<()> {
+            let start = Instant::now();
+            loop {
+                let mut available = 0;
+                // SAFETY: Handle is a valid pipe instance, no buffer is given to copy into.
+                let peeked = unsafe {
+                    namedpipeapi::PeekNamedPipe(
+                        self.file.as_raw_handle() as _,
+                        ptr::null_mut(),
+                        0,
+                        ptr::null_mut(),
+                        &mut available,
+                        ptr::null_mut(),
+                    )
+                };
+                if peeked == 0 || available > 0 {
+                    return Ok(());
+                }
+                if start.elapsed() >= timeout {
+                    return Err(io::ErrorKind::TimedOut.into());
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+    }
+
+    impl Read for Pipe {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if let Some(timeout) = self.read_timeout {
+                self.wait_readable(timeout)?;
+            }
+            self.file.read(buf)
+        }
+    }
+
+    impl Write for Pipe {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.file.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            // SAFETY: Handle is a valid pipe instance.
+            if unsafe { fileapi::FlushFileBuffers(self.file.as_raw_handle() as _) } == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,152 @@
+#[cfg(unix)]
+fn request(args: &[&str]) -> super::Request {
+    super::Request {
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        working_directory: None,
+    }
+}
+
+/// A request sent while another runs waits until that one finished.
+#[cfg(unix)]
+#[test]
+fn test_daemon_serialises_requests() {
+    use std::{
+        sync::{mpsc, Arc, Mutex},
+        time::Duration,
+    };
+
+    use super::{send, Daemon, Response};
+
+    let dir = tempfile::tempdir().unwrap();
+    let address = dir.path().join("daemon.sock").display().to_string();
+    let mut daemon = Daemon::bind(&address).unwrap();
+    let events = Arc::new(Mutex::new(vec![]));
+    let log = |events: &Arc<Mutex<Vec<String>>>, event: String| events.lock().unwrap().push(event);
+    let (started_tx, started_rx) = mpsc::channel();
+    let (finish_tx, finish_rx) = mpsc::channel::<()>();
+    let server = {
+        let events = events.clone();
+        std::thread::spawn(move || {
+            for _ in 0..2 {
+                let client = daemon.accept().unwrap();
+                client
+                    .serve(|request| {
+                        let name = request.args[0].clone();
+                        log(&events, format!("start {name}"));
+                        if name == "a" {
+                            started_tx.send(()).unwrap();
+                            finish_rx.recv().unwrap();
+                        }
+                        log(&events, format!("end {name}"));
+                        Response {
+                            error: (name == "b").then(|| "window not found".to_owned()),
+                            stdout: format!("Focusing {name}\n"),
+                            stderr: String::new(),
+                        }
+                    })
+                    .unwrap();
+            }
+        })
+    };
+    let client = |name: &'static str| {
+        let (address, events) = (address.clone(), events.clone());
+        std::thread::spawn(move || {
+            let response = send(&address, &request(&[name])).unwrap();
+            log(&events, format!("response {name}"));
+            response
+        })
+    };
+
+    let a = client("a");
+    started_rx.recv().unwrap();
+    let b = client("b");
+    // `b` is queued, not run, while `a` is still running.
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!b.is_finished());
+    assert_eq!(*events.lock().unwrap(), ["start a"]);
+
+    finish_tx.send(()).unwrap();
+    let response_a = a.join().unwrap();
+    let response_b = b.join().unwrap();
+    server.join().unwrap();
+
+    assert_eq!(response_a.error, None);
+    assert_eq!(response_a.stdout, "Focusing a\n");
+    assert_eq!(response_b.error.as_deref(), Some("window not found"));
+    let events = events.lock().unwrap();
+    let position = |event: &str| events.iter().position(|e| e == event).unwrap();
+    assert!(position("end a") < position("start b"));
+    assert!(position("end b") < position("response b"));
+
+    // Socket of a daemon that is gone is replaced.
+    assert!(Daemon::bind(&address).is_ok());
+}
+
+/// A client that connects and sends nothing is dropped after the read timeout.
+#[cfg(unix)]
+#[test]
+fn test_idle_client() {
+    use std::{os::unix::net::UnixStream, time::Duration};
+
+    use super::{send, Daemon, Response};
+    use crate::error::Error;
+
+    let dir = tempfile::tempdir().unwrap();
+    let address = dir.path().join("daemon.sock").display().to_string();
+    let mut daemon = Daemon::bind(&address).unwrap();
+    daemon.read_timeout = Duration::from_millis(100);
+    let server = std::thread::spawn(move || {
+        let idle = daemon.accept().unwrap().serve(|_| unreachable!());
+        assert!(matches!(idle, Err(Error::Daemon(message)) if message.contains("no request")));
+        daemon
+            .accept()
+            .unwrap()
+            .serve(|_| Response::default())
+            .unwrap();
+    });
+
+    let _idle = UnixStream::connect(&address).unwrap();
+    assert_eq!(
+        send(&address, &request(&["a"])).unwrap(),
+        Response::default()
+    );
+    server.join().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_not_running() {
+    use super::{is_not_running, send};
+
+    let dir = tempfile::tempdir().unwrap();
+    let address = dir.path().join("daemon.sock").display().to_string();
+    let error = send(&address, &request(&[])).unwrap_err();
+    assert!(is_not_running(&error));
+}
+
+/// Only the user can connect, and a socket of another user is never sent to.
+#[cfg(unix)]
+#[test]
+fn test_socket_owner() {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    use super::{send, Daemon};
+    use crate::error::Error;
+
+    let dir = tempfile::tempdir().unwrap();
+    let socket_dir = dir.path().join("helix-win-runner-test");
+    let address = socket_dir.join("daemon.sock").display().to_string();
+    let _daemon = Daemon::bind(&address).unwrap();
+    let mode = |path: &str| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode(socket_dir.to_str().unwrap()), 0o700);
+    assert_eq!(mode(&address), 0o600);
+
+    // Only possible as root, handing the socket to someone else.
+    let uid = std::fs::metadata(&address).unwrap().uid();
+    if std::os::unix::fs::lchown(&address, Some(uid + 1), None).is_err() {
+        return;
+    }
+    let error = send(&address, &request(&[])).unwrap_err();
+    assert!(matches!(error, Error::Daemon(message) if message.contains("another user")));
+    assert!(matches!(Daemon::bind(&address), Err(Error::Daemon(_))));
+}
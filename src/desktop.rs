@@ -0,0 +1,80 @@
+use crate::{
+    error::Result,
+    keyboard_macro::Step,
+    matcher::WindowMatcher,
+    rank::{Pick, Ranker, Score},
+    state::WindowIdentity,
+    window_info::{IgnoreList, WindowInfo},
+};
+
+#[cfg(test)]
+mod test;
+
+#[cfg(all(windows, feature = "windows"))]
+pub mod windows;
+#[cfg(unix)]
+pub mod x11;
+
+/// Desktop whose windows are searched, focused and typed into with simulated keyboard input,
+/// unlike a [`Backend`](crate::backend::Backend) driving Helix through its terminal.
+pub trait Desktop {
+    /// Top-level windows, topmost first.
+    fn windows(&self) -> Result<Vec<WindowInfo>>;
+
+    /// Bring `window` to the front and give it keyboard focus.
+    fn focus(&mut self, window: &WindowInfo) -> Result<()>;
+
+    /// Type `steps` into `window`, focused by `focus`.
+    /// Fails with [`Error::FocusLost`](crate::error::Error::FocusLost)
+    /// when focus moved to another window, unless the desktop was told not to check.
+    fn play(&mut self, window: &WindowInfo, steps: &[Step]) -> Result<()>;
+}
+
+/// How a window is chosen among the desktop windows.
+#[derive(Debug, Clone, Default)]
+pub struct WindowSearch {
+    /// Windows to skip, `None` to search all windows.
+    pub ignore: Option<IgnoreList>,
+    pub matcher: WindowMatcher,
+    pub ranker: Ranker,
+    /// Windows launched by the runner for the project.
+    pub launched: Vec<WindowIdentity>,
+    pub pick: Pick,
+}
+
+impl WindowSearch {
+    /// Windows of `desktop` that are not ignored, topmost first.
+    pub fn windows(&self, desktop: &dyn Desktop) -> Result<Vec<WindowInfo>> {
+        let mut windows = desktop.windows()?;
+        if let Some(ignore) = &self.ignore {
+            windows.retain(|info| !ignore.is_ignored(info));
+        }
+        Ok(windows)
+    }
+
+    pub fn is_match(&self, info: &WindowInfo) -> bool {
+        self.matcher.is_match(&info.process_name, &info.title)
+    }
+
+    /// Matching windows out of `windows` in z-order, in the order `pick` chooses from:
+    /// by process name for `first`, best score first otherwise.
+    pub fn candidates(&self, windows: Vec<WindowInfo>) -> Vec<(Score, WindowInfo)> {
+        let mut candidates: Vec<_> = windows
+            .into_iter()
+            .enumerate()
+            .filter(|(_, info)| self.is_match(info))
+            .map(|(z_order, info)| {
+                let launched = self.launched.iter().any(|identity| identity.is(&info));
+                let score = self
+                    .ranker
+                    .score(&info.process_name, &info.title, z_order, launched);
+                (score, info)
+            })
+            .collect();
+        match self.pick {
+            Pick::First => candidates.sort_by(|a, b| a.1.process_name.cmp(&b.1.process_name)),
+            _ => candidates.sort_by(|a, b| a.0.cmp_best(&b.0)),
+        }
+        candidates
+    }
+}
@@ -0,0 +1,100 @@
+use super::{Desktop, WindowSearch};
+use crate::{
+    error::Result,
+    keyboard_macro::Step,
+    matcher::{MatchMode, Pattern, WindowMatcher},
+    rank::{Pick, Ranker},
+    state::WindowIdentity,
+    window_info::{IgnoreList, WindowInfo},
+};
+
+fn window(handle: usize, process_name: &str, title: &str) -> WindowInfo {
+    WindowInfo {
+        handle,
+        process_id: handle as u32,
+        thread_id: 0,
+        process_name: process_name.to_owned(),
+        title: title.to_owned(),
+        visible: true,
+        width: 800,
+        height: 600,
+        tool_window: false,
+    }
+}
+
+/// Desktop with fixed windows, topmost first.
+struct FakeDesktop(Vec<WindowInfo>);
+
+impl Desktop for FakeDesktop {
+    fn windows(&self) -> Result<Vec<WindowInfo>> {
+        Ok(self.0.clone())
+    }
+
+    fn focus(&mut self, _window: &WindowInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn play(&mut self, _window: &WindowInfo, _steps: &[Step]) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn search(pick: Pick) -> WindowSearch {
+    WindowSearch {
+        ignore: Some(IgnoreList::default()),
+        matcher: WindowMatcher {
+            title: Some(Pattern::new("Helix", MatchMode::Substring, true).unwrap()),
+            ..Default::default()
+        },
+        ranker: Ranker {
+            title: Some("Helix".to_owned()),
+            case_sensitive: true,
+            project_name: Some("my_game".to_owned()),
+            ..Default::default()
+        },
+        launched: vec![],
+        pick,
+    }
+}
+
+#[test]
+fn test_search_windows() {
+    let desktop = FakeDesktop(vec![
+        window(1, "kitty", "Helix"),
+        window(2, "ctfmon.exe", "Default IME"),
+        window(3, "alacritty", "Helix - my_game"),
+    ]);
+    let mut search = search(Pick::Best);
+    let handles = |windows: Vec<WindowInfo>| -> Vec<_> {
+        windows.into_iter().map(|info| info.handle).collect()
+    };
+    assert_eq!(handles(search.windows(&desktop).unwrap()), [1, 3]);
+    search.ignore = None;
+    assert_eq!(handles(search.windows(&desktop).unwrap()), [1, 2, 3]);
+}
+
+#[test]
+fn test_candidates() {
+    let windows = vec![
+        window(1, "wezterm-gui", "Helix"),
+        window(2, "firefox", "Firefox"),
+        window(3, "alacritty", "Helix - my_game"),
+        window(4, "kitty", "Helix - other"),
+    ];
+    let handles = |search: &WindowSearch| -> Vec<_> {
+        search
+            .candidates(windows.clone())
+            .into_iter()
+            .map(|(_score, info)| info.handle)
+            .collect()
+    };
+
+    // Project in title, then exact title, then z-order.
+    let mut best = search(Pick::Best);
+    assert_eq!(handles(&best), [3, 1, 4]);
+    // Launched windows go first.
+    best.launched = vec![WindowIdentity::of(&windows[3])];
+    assert_eq!(handles(&best), [4, 3, 1]);
+
+    assert_eq!(handles(&search(Pick::First)), [3, 4, 1]);
+}
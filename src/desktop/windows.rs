@@ -0,0 +1,57 @@
+use std::ptr::NonNull;
+
+use super::Desktop;
+use crate::{
+    error::{Error, Result},
+    keyboard_macro::{self, Step},
+    window::{attach_thread_input, get_current_thread_id, get_windows, Window},
+    window_info::WindowInfo,
+};
+
+/// Windows desktop, typed into with enigo.
+#[derive(Debug, Clone, Default)]
+pub struct WindowsDesktop {
+    /// Paste commands from the clipboard instead of typing them.
+    pub clipboard: bool,
+    /// Abort a macro when the window lost focus, see `no_focus_check`.
+    pub focus_check: bool,
+}
+
+/// Window of `info` again, failing with [`Error::WindowNotFound`] when it was closed.
+fn open(info: &WindowInfo) -> Result<Window> {
+    let handle = NonNull::new(info.handle as _).ok_or(Error::WindowNotFound)?;
+    Window::open(handle).map_err(|_| Error::WindowNotFound)
+}
+
+impl Desktop for WindowsDesktop {
+    fn windows(&self) -> Result<Vec<WindowInfo>> {
+        Ok(get_windows()?
+            .into_iter()
+            .map(|(info, _window)| info)
+            .collect())
+    }
+
+    fn focus(&mut self, info: &WindowInfo) -> Result<()> {
+        let window = open(info)?;
+        let current_thread_id = get_current_thread_id();
+        attach_thread_input(window.thread_id(), current_thread_id, true)?;
+
+        window.pop_focus()?;
+        window.pop_focus()?;
+        window.pop_focus()?;
+
+        attach_thread_input(window.thread_id(), current_thread_id, false)?;
+        Ok(())
+    }
+
+    fn play(&mut self, info: &WindowInfo, steps: &[Step]) -> Result<()> {
+        let window = open(info)?;
+        keyboard_macro::play(steps, self.clipboard, || {
+            if !self.focus_check || window.is_foreground() {
+                Ok(())
+            } else {
+                Err(Error::FocusLost)
+            }
+        })
+    }
+}
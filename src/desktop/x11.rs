@@ -0,0 +1,366 @@
+use std::{fmt::Display, time::Duration};
+
+use x11rb::{
+    connection::Connection,
+    errors::ReplyError,
+    protocol::{
+        xproto::{
+            AtomEnum, ClientMessageEvent, ConnectionExt as _, EventMask, MapState, Window,
+            KEY_PRESS_EVENT, KEY_RELEASE_EVENT,
+        },
+        xtest::ConnectionExt as _,
+    },
+    rust_connection::RustConnection,
+    wrapper::ConnectionExt as _,
+    CURRENT_TIME,
+};
+
+use super::Desktop;
+use crate::{
+    backend::step_text,
+    error::{Error, Result},
+    keyboard_macro::Step,
+    window_info::WindowInfo,
+};
+
+#[cfg(test)]
+mod test;
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        _NET_CLIENT_LIST,
+        _NET_CLIENT_LIST_STACKING,
+        _NET_ACTIVE_WINDOW,
+        _NET_WM_NAME,
+        _NET_WM_PID,
+        _NET_WM_WINDOW_TYPE,
+        _NET_WM_WINDOW_TYPE_TOOLBAR,
+        _NET_WM_WINDOW_TYPE_UTILITY,
+        UTF8_STRING,
+    }
+}
+
+const XK_SHIFT_L: u32 = 0xffe1;
+
+/// Time given to clients to fetch the new keyboard mapping after a keycode was remapped,
+/// they do it asynchronously after `MappingNotify` and would type the old keysym meanwhile.
+const REMAP_DELAY: Duration = Duration::from_millis(30);
+
+/// How long the window manager gets to activate a window.
+const FOCUS_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// X11 desktop, windows are listed and focused through the window manager (EWMH)
+/// and typed into with XTest.
+///
+/// The handle of a window is its id, the process name comes from `_NET_WM_PID`
+/// and the title from `_NET_WM_NAME`.
+/// Toolbar and utility windows are tool windows.
+/// Focusing fails when the window manager doesn't make the window active.
+pub struct X11 {
+    /// Abort a macro when the window lost focus, see `no_focus_check`.
+    pub focus_check: bool,
+    connection: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+fn x11_error(e: impl Display) -> Error {
+    Error::Backend(format!("X11: {e}"))
+}
+
+/// Keysym typing `c`.
+pub fn keysym(c: char) -> u32 {
+    match c {
+        '\r' | '\n' => 0xff0d,
+        '\x1b' => 0xff1b,
+        '\t' => 0xff09,
+        // Latin-1 keysyms are the same as the code points.
+        ' '..='~' | '\u{a0}'..='\u{ff}' => c as u32,
+        c => 0x0100_0000 + c as u32,
+    }
+}
+
+/// Keysyms of each keycode, from `GetKeyboardMapping`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyboardMapping {
+    pub min_keycode: u8,
+    pub keysyms_per_keycode: u8,
+    pub keysyms: Vec<u32>,
+}
+
+impl KeyboardMapping {
+    fn keycodes(&self) -> impl Iterator<Item = (u8, &[u32])> {
+        let per_keycode = self.keysyms_per_keycode.max(1) as usize;
+        self.keysyms
+            .chunks(per_keycode)
+            .enumerate()
+            .map(|(i, keysyms)| (self.min_keycode.wrapping_add(i as u8), keysyms))
+    }
+
+    /// Keycode typing `keysym` and whether Shift must be held.
+    pub fn find(&self, keysym: u32) -> Option<(u8, bool)> {
+        self.keycodes().find_map(|(keycode, keysyms)| {
+            match keysyms.iter().take(2).position(|k| *k == keysym)? {
+                0 => Some((keycode, false)),
+                _ => Some((keycode, true)),
+            }
+        })
+    }
+
+    /// Keycode without keysyms, remapped to type keysyms missing from the keyboard.
+    pub fn spare(&self) -> Option<u8> {
+        self.keycodes()
+            .find(|(_, keysyms)| keysyms.iter().all(|k| *k == 0))
+            .map(|(keycode, _)| keycode)
+    }
+}
+
+/// What XTest is asked to do to type text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEvent {
+    /// Map a keycode to a keysym, `0` to clear it.
+    Map(u8, u32),
+    Press(u8),
+    Release(u8),
+}
+
+/// Key events typing `text` on a keyboard with `mapping`.
+///
+/// Characters missing from the keyboard are typed by mapping them to the spare keycode,
+/// which is cleared again at the end. They are skipped when there is no spare keycode.
+pub fn key_events(mapping: &KeyboardMapping, text: &str) -> Vec<KeyEvent> {
+    let shift = mapping.find(XK_SHIFT_L).map(|(keycode, _)| keycode);
+    let spare = mapping.spare();
+    let mut remapped = None;
+    let mut events = vec![];
+    for c in text.chars() {
+        let keysym = keysym(c);
+        let (keycode, shifted) = match (mapping.find(keysym), spare) {
+            (Some(key), _) => key,
+            (None, Some(spare)) => {
+                if remapped != Some(keysym) {
+                    events.push(KeyEvent::Map(spare, keysym));
+                    remapped = Some(keysym);
+                }
+                (spare, false)
+            }
+            (None, None) => continue,
+        };
+        let shift = shift.filter(|_| shifted);
+        events.extend(shift.map(KeyEvent::Press));
+        events.extend([KeyEvent::Press(keycode), KeyEvent::Release(keycode)]);
+        events.extend(shift.map(KeyEvent::Release));
+    }
+    if let (Some(spare), Some(_)) = (spare, remapped) {
+        events.push(KeyEvent::Map(spare, 0));
+    }
+    events
+}
+
+impl X11 {
+    /// Connect to the display in `DISPLAY`.
+    pub fn connect(focus_check: bool) -> Result<X11> {
+        let (connection, screen) = x11rb::connect(None).map_err(x11_error)?;
+        let root = connection.setup().roots[screen].root;
+        let atoms = Atoms::new(&connection)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+        Ok(X11 {
+            focus_check,
+            connection,
+            root,
+            atoms,
+        })
+    }
+
+    fn property32(
+        &self,
+        window: Window,
+        property: u32,
+        type_: impl Into<u32>,
+    ) -> std::result::Result<Vec<u32>, ReplyError> {
+        let reply = self
+            .connection
+            .get_property(false, window, property, type_, 0, u32::MAX)?
+            .reply()?;
+        Ok(reply.value32().map(Iterator::collect).unwrap_or_default())
+    }
+
+    fn property_string(
+        &self,
+        window: Window,
+        property: u32,
+        type_: impl Into<u32>,
+    ) -> std::result::Result<Option<String>, ReplyError> {
+        let reply = self
+            .connection
+            .get_property(false, window, property, type_, 0, u32::MAX)?
+            .reply()?;
+        Ok((reply.format == 8 && !reply.value.is_empty())
+            .then(|| String::from_utf8_lossy(&reply.value).into_owned()))
+    }
+
+    fn active_window(&self) -> std::result::Result<Option<Window>, ReplyError> {
+        let active = self.property32(self.root, self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW)?;
+        Ok(active.first().copied().filter(|window| *window != 0))
+    }
+
+    /// Ids of top-level windows, topmost first when the window manager gives the stacking order.
+    fn window_ids(&self) -> std::result::Result<Vec<Window>, ReplyError> {
+        let mut ids = self.property32(
+            self.root,
+            self.atoms._NET_CLIENT_LIST_STACKING,
+            AtomEnum::WINDOW,
+        )?;
+        ids.reverse();
+        if ids.is_empty() {
+            ids = self.property32(self.root, self.atoms._NET_CLIENT_LIST, AtomEnum::WINDOW)?;
+        }
+        Ok(ids)
+    }
+
+    fn window_info(&self, id: Window) -> std::result::Result<WindowInfo, ReplyError> {
+        let title =
+            match self.property_string(id, self.atoms._NET_WM_NAME, self.atoms.UTF8_STRING)? {
+                Some(title) => title,
+                None => self
+                    .property_string(id, AtomEnum::WM_NAME.into(), AtomEnum::STRING)?
+                    .unwrap_or_default(),
+            };
+        let process_id = self
+            .property32(id, self.atoms._NET_WM_PID, AtomEnum::CARDINAL)?
+            .first()
+            .copied();
+        let window_types = self.property32(id, self.atoms._NET_WM_WINDOW_TYPE, AtomEnum::ATOM)?;
+        let attributes = self.connection.get_window_attributes(id)?.reply()?;
+        let geometry = self.connection.get_geometry(id)?.reply()?;
+        Ok(WindowInfo {
+            handle: id as usize,
+            process_id: process_id.unwrap_or(0),
+            thread_id: 0,
+            process_name: process_id.map(process_name).unwrap_or_default(),
+            title,
+            visible: attributes.map_state == MapState::VIEWABLE,
+            width: geometry.width.into(),
+            height: geometry.height.into(),
+            tool_window: window_types.iter().any(|window_type| {
+                [
+                    self.atoms._NET_WM_WINDOW_TYPE_TOOLBAR,
+                    self.atoms._NET_WM_WINDOW_TYPE_UTILITY,
+                ]
+                .contains(window_type)
+            }),
+        })
+    }
+
+    fn keyboard_mapping(&self) -> std::result::Result<KeyboardMapping, ReplyError> {
+        let setup = self.connection.setup();
+        let count = setup.max_keycode - setup.min_keycode + 1;
+        let reply = self
+            .connection
+            .get_keyboard_mapping(setup.min_keycode, count)?
+            .reply()?;
+        Ok(KeyboardMapping {
+            min_keycode: setup.min_keycode,
+            keysyms_per_keycode: reply.keysyms_per_keycode,
+            keysyms: reply.keysyms,
+        })
+    }
+
+    fn key(&self, keycode: u8, press: bool) -> std::result::Result<(), ReplyError> {
+        let type_ = if press {
+            KEY_PRESS_EVENT
+        } else {
+            KEY_RELEASE_EVENT
+        };
+        self.connection
+            .xtest_fake_input(type_, keycode, CURRENT_TIME, self.root, 0, 0, 0)?;
+        Ok(())
+    }
+
+    fn type_text(&self, text: &str) -> std::result::Result<(), ReplyError> {
+        let mapping = self.keyboard_mapping()?;
+        let per_keycode = mapping.keysyms_per_keycode;
+        for event in key_events(&mapping, text) {
+            match event {
+                KeyEvent::Map(keycode, keysym) => {
+                    let keysyms = vec![keysym; per_keycode as usize];
+                    self.connection
+                        .change_keyboard_mapping(1, keycode, per_keycode, &keysyms)?;
+                    self.connection.sync()?;
+                    std::thread::sleep(REMAP_DELAY);
+                }
+                KeyEvent::Press(keycode) => self.key(keycode, true)?,
+                KeyEvent::Release(keycode) => self.key(keycode, false)?,
+            }
+            self.connection.sync()?;
+        }
+        Ok(())
+    }
+}
+
+/// Name of the process with `process_id`, from `/proc`.
+fn process_name(process_id: u32) -> String {
+    std::fs::read_to_string(format!("/proc/{process_id}/comm"))
+        .map(|name| name.trim_end().to_owned())
+        .unwrap_or_default()
+}
+
+impl Desktop for X11 {
+    fn windows(&self) -> Result<Vec<WindowInfo>> {
+        let mut windows = vec![];
+        for id in self.window_ids().map_err(x11_error)? {
+            match self.window_info(id) {
+                Ok(info) => windows.push(info),
+                // Closed while listing.
+                Err(ReplyError::X11Error(_)) => continue,
+                Err(e) => return Err(x11_error(e)),
+            }
+        }
+        Ok(windows)
+    }
+
+    fn focus(&mut self, window: &WindowInfo) -> Result<()> {
+        let id = window.handle as Window;
+        // Source indication 2 is a pager, so the window manager doesn't refuse it.
+        let event = ClientMessageEvent::new(
+            32,
+            id,
+            self.atoms._NET_ACTIVE_WINDOW,
+            [2, CURRENT_TIME, 0, 0, 0],
+        );
+        self.connection
+            .send_event(
+                false,
+                self.root,
+                EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+                event,
+            )
+            .map_err(x11_error)?;
+        self.connection.flush().map_err(x11_error)?;
+        // The window manager activates the window asynchronously.
+        let start = std::time::Instant::now();
+        while self.active_window().map_err(x11_error)? != Some(id) {
+            if start.elapsed() > FOCUS_TIMEOUT {
+                return Err(x11_error(format_args!(
+                    "window manager did not activate window {id:#x} within {} seconds",
+                    FOCUS_TIMEOUT.as_secs_f64()
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(25));
+        }
+        Ok(())
+    }
+
+    fn play(&mut self, window: &WindowInfo, steps: &[Step]) -> Result<()> {
+        let id = window.handle as Window;
+        for step in steps {
+            if self.focus_check && self.active_window().map_err(x11_error)? != Some(id) {
+                return Err(Error::FocusLost);
+            }
+            self.type_text(&step_text(step)).map_err(x11_error)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,264 @@
+use super::{key_events, keysym, KeyEvent, KeyboardMapping};
+
+#[test]
+fn test_keysym() {
+    assert_eq!(keysym('a'), 0x61);
+    assert_eq!(keysym(':'), 0x3a);
+    assert_eq!(keysym('é'), 0xe9);
+    assert_eq!(keysym('ก'), 0x0100_0e01);
+    assert_eq!(keysym('\r'), 0xff0d);
+    assert_eq!(keysym('\x1b'), 0xff1b);
+}
+
+fn mapping() -> KeyboardMapping {
+    KeyboardMapping {
+        min_keycode: 8,
+        keysyms_per_keycode: 3,
+        keysyms: vec![
+            0xff1b, 0, 0, // 8: Escape
+            0x61, 0x41, 0, // 9: a A
+            0x3b, 0x3a, 0, // 10: ; :
+            0, 0, 0, // 11: nothing
+            0xffe1, 0, 0, // 12: Shift_L
+        ],
+    }
+}
+
+#[test]
+fn test_keyboard_mapping() {
+    let mapping = mapping();
+    assert_eq!(mapping.find(0x61), Some((9, false)));
+    assert_eq!(mapping.find(0x41), Some((9, true)));
+    assert_eq!(mapping.find(0x3a), Some((10, true)));
+    assert_eq!(mapping.find(0xffe1), Some((12, false)));
+    assert_eq!(mapping.find(0xe9), None);
+    assert_eq!(mapping.spare(), Some(11));
+}
+
+#[test]
+fn test_key_events() {
+    use KeyEvent::{Map, Press, Release};
+
+    let mapping = mapping();
+    assert_eq!(
+        key_events(&mapping, "aA:"),
+        [
+            Press(9),
+            Release(9),
+            Press(12),
+            Press(9),
+            Release(9),
+            Release(12),
+            Press(12),
+            Press(10),
+            Release(10),
+            Release(12),
+        ]
+    );
+    // Missing keysyms go to the spare keycode, remapped only when the keysym changes.
+    assert_eq!(
+        key_events(&mapping, "éé\x1bก"),
+        [
+            Map(11, 0xe9),
+            Press(11),
+            Release(11),
+            Press(11),
+            Release(11),
+            Press(8),
+            Release(8),
+            Map(11, 0x0100_0e01),
+            Press(11),
+            Release(11),
+            Map(11, 0),
+        ]
+    );
+
+    // Without a spare keycode missing characters are skipped,
+    // and without Shift shifted keysyms are typed with their key alone.
+    let mapping = KeyboardMapping {
+        keysyms: mapping.keysyms[..9].to_vec(),
+        ..mapping
+    };
+    assert_eq!(key_events(&mapping, "éA"), [Press(9), Release(9)]);
+}
+
+/// Keysyms typed into `window` as a client sees them, reading events until `count` arrived.
+/// The keyboard mapping is fetched again on `MappingNotify` like clients do.
+fn typed_keysyms(observer: &super::X11, window: u32, count: usize) -> Vec<u32> {
+    use x11rb::{
+        connection::Connection,
+        protocol::{xproto::KeyButMask, Event},
+    };
+
+    let mut mapping = observer.keyboard_mapping().unwrap();
+    let mut typed = vec![];
+    let start = std::time::Instant::now();
+    while typed.len() < count {
+        assert!(start.elapsed().as_secs() < 5, "typed only {typed:x?}");
+        match observer.connection.poll_for_event().unwrap() {
+            Some(Event::MappingNotify(_)) => mapping = observer.keyboard_mapping().unwrap(),
+            Some(Event::KeyPress(event)) if event.event == window => {
+                let shifted = u16::from(event.state) & u16::from(KeyButMask::SHIFT) != 0;
+                let keysyms = mapping
+                    .keycodes()
+                    .find(|(keycode, _)| *keycode == event.detail)
+                    .map(|(_, keysyms)| keysyms.to_vec())
+                    .unwrap_or_default();
+                let keysym = match (keysyms.get(shifted as usize), keysyms.first()) {
+                    (Some(&keysym), _) if keysym != 0 => keysym,
+                    (_, Some(&keysym)) => keysym,
+                    _ => 0,
+                };
+                if keysym != super::XK_SHIFT_L {
+                    typed.push(keysym);
+                }
+            }
+            Some(_) => {}
+            None => std::thread::sleep(std::time::Duration::from_millis(5)),
+        }
+    }
+    typed
+}
+
+/// Runs only with a display, like under `xvfb-run cargo test`.
+/// Without a window manager, this test sets the client list and active window itself.
+/// Listing, focusing and typing share the display, so they are checked one after another.
+#[test]
+fn test_desktop() {
+    use x11rb::{
+        connection::Connection,
+        protocol::xproto::{
+            AtomEnum, ConnectionExt as _, CreateWindowAux, EventMask, InputFocus, MapState,
+            PropMode, WindowClass,
+        },
+        wrapper::ConnectionExt as _,
+        COPY_DEPTH_FROM_PARENT, CURRENT_TIME,
+    };
+
+    use super::X11;
+    use crate::{desktop::Desktop, keyboard_macro::Step};
+
+    if std::env::var_os("DISPLAY").is_none() {
+        return;
+    }
+    let observer = X11::connect(true).unwrap();
+    let connection = &observer.connection;
+    let root = observer.root;
+    let window = connection.generate_id().unwrap();
+    connection
+        .create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0,
+            0,
+            100,
+            100,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            0,
+            &CreateWindowAux::new().event_mask(EventMask::KEY_PRESS),
+        )
+        .unwrap();
+    let title = format!("helix-win-runner test {}", std::process::id());
+    let atoms = &observer.atoms;
+    connection
+        .change_property8(
+            PropMode::REPLACE,
+            window,
+            atoms._NET_WM_NAME,
+            atoms.UTF8_STRING,
+            title.as_bytes(),
+        )
+        .unwrap();
+    connection
+        .change_property32(
+            PropMode::REPLACE,
+            window,
+            atoms._NET_WM_PID,
+            AtomEnum::CARDINAL,
+            &[std::process::id()],
+        )
+        .unwrap();
+    connection.map_window(window).unwrap();
+    let has_window_manager = !observer.window_ids().unwrap().is_empty();
+    let set_root_window = |property: u32, value: u32| {
+        connection
+            .change_property32(
+                PropMode::REPLACE,
+                root,
+                property,
+                AtomEnum::WINDOW,
+                &[value],
+            )
+            .unwrap();
+    };
+    if !has_window_manager {
+        set_root_window(atoms._NET_CLIENT_LIST, window);
+    }
+    connection.sync().unwrap();
+
+    // Listing.
+    let mut x11 = X11::connect(true).unwrap();
+    let start = std::time::Instant::now();
+    let found = loop {
+        let windows = x11.windows().unwrap();
+        match windows.into_iter().find(|info| info.title == title) {
+            Some(found) => break found,
+            None if start.elapsed().as_secs() < 5 => {
+                std::thread::sleep(std::time::Duration::from_millis(50))
+            }
+            None => panic!("window not found"),
+        }
+    };
+    assert_eq!(found.handle, window as usize);
+    assert_eq!(found.process_id, std::process::id());
+    assert_eq!((found.width, found.height), (100, 100));
+    assert!(!found.tool_window);
+
+    // Focusing, failing while the window isn't active.
+    if !has_window_manager {
+        assert!(x11.focus(&found).is_err());
+        while connection
+            .get_window_attributes(window)
+            .unwrap()
+            .reply()
+            .unwrap()
+            .map_state
+            != MapState::VIEWABLE
+        {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        connection
+            .set_input_focus(InputFocus::PARENT, window, CURRENT_TIME)
+            .unwrap();
+        set_root_window(atoms._NET_ACTIVE_WINDOW, window);
+        connection.sync().unwrap();
+    }
+    x11.focus(&found).unwrap();
+    assert_eq!(observer.active_window().unwrap(), Some(window));
+
+    // Typing characters missing from the keyboard through the spare keycode.
+    let text = "a:éกé";
+    let mapping = x11.keyboard_mapping().unwrap();
+    let typing = std::thread::spawn(move || {
+        x11.play(&found, &[Step::Keys(text.to_owned())]).unwrap();
+        x11
+    });
+    let typed = typed_keysyms(&observer, window, text.chars().count());
+    let x11 = typing.join().unwrap();
+    assert_eq!(typed, text.chars().map(super::keysym).collect::<Vec<_>>());
+    // The spare keycode is free again.
+    assert_eq!(x11.keyboard_mapping().unwrap(), mapping);
+
+    if !has_window_manager {
+        connection
+            .delete_property(root, atoms._NET_CLIENT_LIST)
+            .unwrap();
+        connection
+            .delete_property(root, atoms._NET_ACTIVE_WINDOW)
+            .unwrap();
+    }
+    connection.destroy_window(window).unwrap();
+    connection.sync().unwrap();
+}
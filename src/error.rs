@@ -4,8 +4,37 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("window not found")]
     WindowNotFound,
+    #[error("window not found after waiting {0} seconds")]
+    WindowTimeout(f64),
+    #[error("focus moved away from the Helix window, macro aborted")]
+    FocusLost,
+    #[error("{0} windows matched the search")]
+    AmbiguousWindow(usize),
+    #[error("no window picked")]
+    Cancelled,
     #[error("atleast one of `window_title` or `window_process_name` argument must provided")]
     IncompleteSearchArgument,
+    #[error("invalid search pattern: {0}")]
+    InvalidPattern(String),
+    #[error("Godot project not found, no `project.godot` in the project path or its parents")]
+    GodotProjectNotFound,
+    #[error("no resource with `{0}` found in the Godot project")]
+    GodotUidNotFound(String),
+    #[error("`per_project` needs a window title, which the `{0}` launcher can't set; use `run` with a titled terminal instead")]
+    UntitledLauncher(String),
+    #[error("the `{0}` launcher is not available on this platform")]
+    UnsupportedLauncher(String),
+    #[error("invalid run command: {0}")]
+    InvalidCommand(String),
+    #[error("{0}")]
+    Backend(String),
+    #[error("daemon: {0}")]
+    Daemon(String),
+    #[error("invalid config `{}`: {message}", path.display())]
+    Config {
+        path: std::path::PathBuf,
+        message: String,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,184 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use crate::error::{Error, Result};
+
+#[cfg(test)]
+mod test;
+
+const RES_SCHEME: &str = "res://";
+const UID_SCHEME: &str = "uid://";
+
+/// Find Godot project root by looking for `project.godot` in `start` and its ancestors.
+pub fn find_project_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join("project.godot").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Resolve `res://` and `uid://` paths to filesystem paths.
+///
+/// Project root is searched from `project_path` or the current directory.
+/// Returns `None` if `path` is neither of those.
+pub fn resolve_path(project_path: Option<&str>, path: &str) -> Result<Option<String>> {
+    if !path.starts_with(RES_SCHEME) && !path.starts_with(UID_SCHEME) {
+        return Ok(None);
+    }
+    let start = match project_path {
+        Some(project_path) => PathBuf::from(project_path),
+        None => std::env::current_dir()?,
+    };
+    let root = find_project_root(&start).ok_or(Error::GodotProjectNotFound)?;
+
+    let res_path = if path.starts_with(UID_SCHEME) {
+        find_uid(&root, path)?.ok_or_else(|| Error::GodotUidNotFound(path.to_owned()))?
+    } else {
+        path.to_owned()
+    };
+    Ok(Some(res_path_to_path(&root, &res_path)))
+}
+
+fn res_path_to_path(root: &Path, res_path: &str) -> String {
+    let relative = res_path.strip_prefix(RES_SCHEME).unwrap_or(res_path);
+    let root = root.to_string_lossy();
+    let root = root.trim_end_matches(['/', '\\']);
+    if relative.is_empty() {
+        root.to_owned()
+    } else {
+        format!("{root}/{relative}")
+    }
+}
+
+/// Find the `res://` path of `uid`.
+///
+/// Looks in the editor's `.godot/uid_cache.bin` first, then scans the project for
+/// `.uid` sidecar files (Godot 4.4), `.import` metadata of imported assets
+/// and the header of text resources.
+pub fn find_uid(root: &Path, uid: &str) -> Result<Option<String>> {
+    let cached = uid_to_id(uid).and_then(|id| find_cached_uid(root, id));
+    // The cache can be stale, only trust it when the file is still there.
+    if let Some(res_path) =
+        cached.filter(|res_path| Path::new(&res_path_to_path(root, res_path)).exists())
+    {
+        return Ok(Some(res_path));
+    }
+
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        if dir.join(".gdignore").exists() {
+            continue;
+        }
+        // Unreadable directories can't declare the uid anyway.
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if !name.starts_with('.') {
+                    stack.push(path);
+                }
+                continue;
+            }
+            let Some(source) = uid_source(&path, &name, uid) else {
+                continue;
+            };
+            let Ok(relative) = source.strip_prefix(root) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            return Ok(Some(format!("{RES_SCHEME}{relative}")));
+        }
+    }
+    Ok(None)
+}
+
+/// Numeric id of a `uid://` text, the inverse of Godot's `ResourceUID::id_to_text`.
+///
+/// The text is the id in base 36 with `a`-`z` as 0-25 and `0`-`9` as 26-35.
+pub fn uid_to_id(uid: &str) -> Option<u64> {
+    let digits = uid.strip_prefix(UID_SCHEME)?;
+    if digits.is_empty() {
+        return None;
+    }
+    let mut id: u64 = 0;
+    for c in digits.bytes() {
+        let digit = match c {
+            b'a'..=b'z' => c - b'a',
+            b'0'..=b'9' => c - b'0' + 26,
+            _ => return None,
+        };
+        id = id.wrapping_mul(36).wrapping_add(digit as u64);
+    }
+    Some(id & 0x7fff_ffff_ffff_ffff)
+}
+
+/// Look `id` up in `.godot/uid_cache.bin`, written by the editor.
+///
+/// The cache is a little endian `u32` count followed by entries of
+/// a `u64` id, a `u32` length and that many bytes of `res://` path.
+fn find_cached_uid(root: &Path, id: u64) -> Option<String> {
+    let cache = fs::read(root.join(".godot").join("uid_cache.bin")).ok()?;
+    let mut rest = cache.as_slice();
+    let mut take = |n: usize| {
+        let (taken, tail) = rest.split_at_checked(n)?;
+        rest = tail;
+        Some(taken)
+    };
+    let count = u32::from_le_bytes(take(4)?.try_into().ok()?);
+    for _ in 0..count {
+        let entry_id = u64::from_le_bytes(take(8)?.try_into().ok()?);
+        let length = u32::from_le_bytes(take(4)?.try_into().ok()?);
+        let path = take(length as usize)?;
+        if entry_id & 0x7fff_ffff_ffff_ffff == id {
+            return String::from_utf8(path.to_vec()).ok();
+        }
+    }
+    None
+}
+
+/// First line of `path`, without reading the rest of the file.
+fn first_line(path: &Path) -> Option<String> {
+    let mut line = String::new();
+    BufReader::new(fs::File::open(path).ok()?)
+        .read_line(&mut line)
+        .ok()?;
+    Some(line)
+}
+
+/// Return the file that `uid` refers to if `path` declares it.
+fn uid_source(path: &Path, name: &str, uid: &str) -> Option<PathBuf> {
+    if let Some(source) = name.strip_suffix(".uid") {
+        let declared = first_line(path)?;
+        return (declared.trim() == uid).then(|| path.with_file_name(source));
+    }
+    if let Some(source) = name.strip_suffix(".import") {
+        let file = BufReader::new(fs::File::open(path).ok()?);
+        let declared = file
+            .lines()
+            .map_while(std::result::Result::ok)
+            .find_map(|line| line.strip_prefix("uid=").map(str::to_owned))?;
+        return (unquote(&declared) == uid).then(|| path.with_file_name(source));
+    }
+    if name.ends_with(".tscn") || name.ends_with(".tres") {
+        let header = first_line(path)?;
+        let declared = header
+            .split_whitespace()
+            .find_map(|attr| attr.strip_prefix("uid="))?;
+        return (unquote(declared.trim_end_matches(']')) == uid).then(|| path.to_path_buf());
+    }
+    None
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim().trim_matches('"')
+}
@@ -0,0 +1,133 @@
+use std::fs;
+
+use super::{find_uid, resolve_path, uid_to_id};
+
+fn project() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("project.godot"), "config_version=5\n").unwrap();
+    fs::create_dir_all(dir.path().join("scripts/enemy")).unwrap();
+    fs::create_dir_all(dir.path().join(".godot")).unwrap();
+    dir
+}
+
+#[test]
+fn test_resolve_res_path() {
+    let dir = project();
+    let project_path = dir.path().join("scripts");
+    let project_path = project_path.to_str().unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    assert_eq!(
+        resolve_path(Some(project_path), "res://scripts/player.gd").unwrap(),
+        Some(format!("{root}/scripts/player.gd"))
+    );
+    assert_eq!(
+        resolve_path(Some(project_path), "C:/scripts/player.gd").unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_find_uid() {
+    let dir = project();
+    let root = dir.path();
+    fs::write(root.join("scripts/enemy/enemy.gd.uid"), "uid://b5x2k7\n").unwrap();
+    fs::write(
+        root.join("icon.svg.import"),
+        "[remap]\n\nimporter=\"texture\"\nuid=\"uid://c3m8x\"\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("main.tscn"),
+        "[gd_scene load_steps=2 format=3 uid=\"uid://dq4a1\"]\n",
+    )
+    .unwrap();
+    fs::write(root.join(".godot/ignored.gd.uid"), "uid://ignored\n").unwrap();
+
+    assert_eq!(
+        find_uid(root, "uid://b5x2k7").unwrap().as_deref(),
+        Some("res://scripts/enemy/enemy.gd")
+    );
+    assert_eq!(
+        find_uid(root, "uid://c3m8x").unwrap().as_deref(),
+        Some("res://icon.svg")
+    );
+    assert_eq!(
+        find_uid(root, "uid://dq4a1").unwrap().as_deref(),
+        Some("res://main.tscn")
+    );
+    assert_eq!(find_uid(root, "uid://ignored").unwrap(), None);
+    assert!(resolve_path(root.to_str(), "uid://missing").is_err());
+}
+
+fn write_uid_cache(root: &std::path::Path, entries: &[(&str, &str)]) {
+    let mut cache = (entries.len() as u32).to_le_bytes().to_vec();
+    for (uid, res_path) in entries {
+        cache.extend(uid_to_id(uid).unwrap().to_le_bytes());
+        cache.extend((res_path.len() as u32).to_le_bytes());
+        cache.extend(res_path.as_bytes());
+    }
+    fs::write(root.join(".godot/uid_cache.bin"), cache).unwrap();
+}
+
+#[test]
+fn test_uid_to_id() {
+    assert_eq!(uid_to_id("uid://a"), Some(0));
+    assert_eq!(uid_to_id("uid://9"), Some(35));
+    assert_eq!(uid_to_id("uid://ba"), Some(36));
+    assert_eq!(uid_to_id("uid://"), None);
+    assert_eq!(uid_to_id("uid://B5"), None);
+    assert_eq!(uid_to_id("res://a"), None);
+}
+
+#[test]
+fn test_find_uid_in_cache() {
+    let dir = project();
+    let root = dir.path();
+    fs::write(root.join("scripts/player.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("scripts/enemy/enemy.gd.uid"), "uid://enemy2\n").unwrap();
+    write_uid_cache(
+        root,
+        &[
+            ("uid://player1", "res://scripts/player.gd"),
+            // Stale entry, the file moved since.
+            ("uid://enemy2", "res://enemy.gd"),
+        ],
+    );
+
+    assert_eq!(
+        find_uid(root, "uid://player1").unwrap().as_deref(),
+        Some("res://scripts/player.gd")
+    );
+    assert_eq!(
+        find_uid(root, "uid://enemy2").unwrap().as_deref(),
+        Some("res://scripts/enemy/enemy.gd")
+    );
+
+    // A truncated cache falls back to scanning.
+    fs::write(root.join(".godot/uid_cache.bin"), [5, 0, 0, 0, 1]).unwrap();
+    assert_eq!(
+        find_uid(root, "uid://enemy2").unwrap().as_deref(),
+        Some("res://scripts/enemy/enemy.gd")
+    );
+}
+
+/// Unreadable directories are skipped instead of failing the search.
+#[cfg(unix)]
+#[test]
+fn test_find_uid_unreadable_directory() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = project();
+    let root = dir.path();
+    let locked = root.join("locked");
+    fs::create_dir(&locked).unwrap();
+    fs::write(root.join("scripts/enemy/enemy.gd.uid"), "uid://enemy2\n").unwrap();
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+    let found = find_uid(root, "uid://enemy2");
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+    assert_eq!(
+        found.unwrap().as_deref(),
+        Some("res://scripts/enemy/enemy.gd")
+    );
+}
@@ -0,0 +1,11 @@
+use super::parse_secs;
+
+#[test]
+fn test_parse_secs() {
+    assert_eq!(parse_secs("0"), Ok(0.0));
+    assert_eq!(parse_secs("0.25"), Ok(0.25));
+    assert!(parse_secs("-1").is_err());
+    assert!(parse_secs("inf").is_err());
+    assert!(parse_secs("NaN").is_err());
+    assert!(parse_secs("soon").is_err());
+}
@@ -0,0 +1,88 @@
+use enigo::{Key, KeyboardControllable};
+
+use super::{sleep, Step};
+use crate::error::Result;
+
+fn enigo() -> std::sync::MutexGuard<'static, enigo::Enigo> {
+    use enigo::Enigo;
+    use once_cell::sync::Lazy;
+    use std::sync::Mutex;
+
+    static ENIGO: Lazy<Mutex<Enigo>> = Lazy::new(|| Mutex::new(Enigo::new()));
+    ENIGO.lock().unwrap()
+}
+
+fn quote(e: &mut std::sync::MutexGuard<'static, enigo::Enigo>) {
+    e.key_down(Key::Shift);
+    e.key_click(Key::Layout('\''));
+    e.key_up(Key::Shift);
+}
+
+/// Type `steps` into the focused window.
+///
+/// `check` is called before each step, the macro is aborted if it returns an error.
+/// Clipboard is always restored before the next step.
+pub fn play(steps: &[Step], clipboard: bool, mut check: impl FnMut() -> Result<()>) -> Result<()> {
+    for step in steps {
+        check()?;
+        let mut enigo = enigo();
+        match step {
+            Step::Escape => enigo.key_click(Key::Escape),
+            Step::Command(command) => {
+                enigo.key_down(Key::Shift);
+                enigo.key_click(Key::Layout(';'));
+                enigo.key_up(Key::Shift);
+                if clipboard {
+                    paste_restore(&mut enigo, command);
+                } else {
+                    for (i, part) in command.split('"').enumerate() {
+                        if i > 0 {
+                            quote(&mut enigo);
+                        }
+                        enigo.key_sequence(part);
+                    }
+                }
+                enigo.key_click(Key::Return);
+            }
+            Step::Keys(keys) => enigo.key_sequence(keys),
+        }
+    }
+    Ok(())
+}
+
+pub fn paste_restore(enigo: &mut enigo::Enigo, msg: &str) {
+    let mut clipboard_store = None;
+    clipboard_win::with_clipboard_attempts(10, || {
+        clipboard_store = ClipboardStore::save().expect("To get clipboard");
+        clipboard_win::set(clipboard_win::formats::Unicode, msg).expect("To set clipboard")
+    })
+    .expect("To open clipboard");
+    sleep(0.05);
+    enigo.key_down(Key::Control);
+    enigo.key_down(Key::Layout('v'));
+    enigo.key_up(Key::Layout('v'));
+    enigo.key_up(Key::Control);
+    sleep(0.05);
+    if let Some(store) = clipboard_store.take() {
+        clipboard_win::with_clipboard_attempts(10, move || {
+            store.restore().expect("To set clipboard")
+        })
+        .expect("To open clipboard")
+    }
+}
+
+struct ClipboardStore(clipboard_win::types::c_uint, Vec<u8>);
+
+impl ClipboardStore {
+    fn save() -> clipboard_win::SysResult<Option<ClipboardStore>> {
+        let mut enum_formats = clipboard_win::EnumFormats::new();
+        let Some(format) = enum_formats.next() else {
+            return Ok(None);
+        };
+        let content = clipboard_win::get(clipboard_win::formats::RawData(format))?;
+        Ok(Some(ClipboardStore(format, content)))
+    }
+    fn restore(&self) -> clipboard_win::SysResult<()> {
+        clipboard_win::set(clipboard_win::formats::RawData(self.0), self.1.to_vec())
+    }
+}
@@ -0,0 +1,136 @@
+use crate::command::quote_arg;
+
+#[cfg(test)]
+mod test;
+
+/// Terminal to launch Helix in when no window is found.
+/// `windows-terminal`, `conhost` and `wsl` only exist on Windows, `kitty` only outside of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Launcher {
+    /// New Windows Terminal window, `wt nt`.
+    WindowsTerminal,
+    /// `wezterm start`. WezTerm can't be given a window title.
+    Wezterm,
+    Alacritty,
+    Kitty,
+    /// Plain console window.
+    Conhost,
+    /// `wsl.exe` in a console window, starting in the project directory.
+    Wsl,
+}
+
+/// How the location is given to Helix after launching it with `run`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LaunchMode {
+    /// Type `:cd` and `:open` into the new window.
+    #[default]
+    Macro,
+    /// Add the project and file to the end of the `run` command as `hx` arguments,
+    /// so nothing is typed.
+    Args,
+}
+
+/// `hx` arguments to start in `project` with `file` opened at `line` and `column`.
+pub fn helix_args(project: Option<&str>, file: Option<(&str, u32, u32)>) -> Vec<String> {
+    let mut args = vec![];
+    if let Some(project) = project {
+        args.push("-w".to_owned());
+        args.push(project.to_owned());
+    }
+    if let Some((file, line, column)) = file {
+        args.push(format!("{file}:{line}:{column}"));
+    }
+    args
+}
+
+/// What a [`Launcher`] fills in: the `run` command and how to find its window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchPreset {
+    /// Command to give to `run`, may contain placeholders.
+    pub command: String,
+    /// Title to search for, `None` when the terminal can't set one.
+    pub window_title: Option<String>,
+    pub window_process_name: Option<String>,
+}
+
+/// File name of `program` without directory, like `hx` for `C:\\Helix\\hx.exe`.
+pub fn program_name(program: &str) -> &str {
+    let name = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    name.strip_suffix(".exe").unwrap_or(name)
+}
+
+impl Launcher {
+    /// Whether the terminal runs on the current platform.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Launcher::Wezterm | Launcher::Alacritty => true,
+            Launcher::WindowsTerminal | Launcher::Conhost | Launcher::Wsl => cfg!(windows),
+            Launcher::Kitty => !cfg!(windows),
+        }
+    }
+
+    /// Whether the terminal can be told the title of its window.
+    /// Only those can be found again by a title, like the project tag of `per_project`.
+    pub fn has_title(self) -> bool {
+        match self {
+            Launcher::WindowsTerminal | Launcher::Alacritty | Launcher::Kitty => true,
+            Launcher::Wezterm | Launcher::Conhost | Launcher::Wsl => false,
+        }
+    }
+
+    /// Preset launching `helix`, usually `hx`, in a window titled `title`.
+    /// The process name is the executable of the current platform, like `alacritty.exe` on Windows.
+    ///
+    /// `distro` is the WSL distribution for `Wsl`, the default one when `None`.
+    /// With `has_project`, `Wsl` starts in the `{project}` directory.
+    pub fn preset(
+        self,
+        title: &str,
+        helix: &str,
+        distro: Option<&str>,
+        has_project: bool,
+    ) -> LaunchPreset {
+        let executable = |name: &str| format!("{name}{}", std::env::consts::EXE_SUFFIX);
+        let quoted_title = quote_arg(title);
+        let quoted_helix = quote_arg(helix);
+        let (command, process_name) = match self {
+            Launcher::WindowsTerminal => (
+                format!("wt nt --title {quoted_title} --suppressApplicationTitle {quoted_helix}"),
+                executable("WindowsTerminal"),
+            ),
+            Launcher::Wezterm => (
+                format!("wezterm start -- {quoted_helix}"),
+                executable("wezterm-gui"),
+            ),
+            Launcher::Alacritty => (
+                format!("alacritty --title {quoted_title} -e {quoted_helix}"),
+                executable("alacritty"),
+            ),
+            Launcher::Kitty => (
+                format!("kitty --title {quoted_title} {quoted_helix}"),
+                executable("kitty"),
+            ),
+            // Console windows are reported as owned by the program running in them.
+            Launcher::Conhost => (
+                format!("conhost.exe {quoted_helix}"),
+                executable(program_name(helix)),
+            ),
+            Launcher::Wsl => {
+                let mut command = "wsl.exe".to_owned();
+                if let Some(distro) = distro {
+                    command.push_str(&format!(" -d {}", quote_arg(distro)));
+                }
+                if has_project {
+                    command.push_str(" --cd {project}");
+                }
+                command.push_str(&format!(" {quoted_helix}"));
+                (command, executable("wsl"))
+            }
+        };
+        LaunchPreset {
+            command,
+            window_title: self.has_title().then(|| title.to_owned()),
+            window_process_name: Some(process_name),
+        }
+    }
+}
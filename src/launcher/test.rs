@@ -0,0 +1,127 @@
+use crate::command::split_command;
+
+use super::{helix_args, program_name, Launcher};
+
+#[test]
+fn test_launcher_preset() {
+    let preset = Launcher::WindowsTerminal.preset("Helix", "hx", None, true);
+    assert_eq!(
+        split_command(&preset.command).unwrap(),
+        [
+            "wt",
+            "nt",
+            "--title",
+            "Helix",
+            "--suppressApplicationTitle",
+            "hx"
+        ]
+    );
+    assert_eq!(preset.window_title.as_deref(), Some("Helix"));
+
+    let preset = Launcher::Wezterm.preset("Helix", "hx", None, true);
+    assert_eq!(preset.window_title, None);
+}
+
+fn process_name(launcher: Launcher, helix: &str) -> String {
+    let preset = launcher.preset("Helix", helix, None, false);
+    preset.window_process_name.unwrap()
+}
+
+#[cfg(windows)]
+#[test]
+fn test_launcher_process_names() {
+    assert_eq!(
+        process_name(Launcher::WindowsTerminal, "hx"),
+        "WindowsTerminal.exe"
+    );
+    assert_eq!(process_name(Launcher::Wezterm, "hx"), "wezterm-gui.exe");
+    assert_eq!(process_name(Launcher::Alacritty, "hx"), "alacritty.exe");
+    assert_eq!(process_name(Launcher::Conhost, "helix"), "helix.exe");
+    assert_eq!(process_name(Launcher::Wsl, "hx"), "wsl.exe");
+    assert!(!Launcher::Kitty.is_supported());
+}
+
+/// Names of `/proc/<pid>/comm`, which X11 windows are matched with.
+#[cfg(not(windows))]
+#[test]
+fn test_launcher_process_names() {
+    assert_eq!(process_name(Launcher::Wezterm, "hx"), "wezterm-gui");
+    assert_eq!(process_name(Launcher::Alacritty, "hx"), "alacritty");
+    assert_eq!(process_name(Launcher::Kitty, "hx"), "kitty");
+    for launcher in [Launcher::WindowsTerminal, Launcher::Conhost, Launcher::Wsl] {
+        assert!(!launcher.is_supported());
+    }
+}
+
+#[test]
+fn test_launcher_preset_title_quoted() {
+    let preset = Launcher::Kitty.preset("Helix - it's", "hx", None, false);
+    assert_eq!(
+        split_command(&preset.command).unwrap(),
+        ["kitty", "--title", "Helix - it's", "hx"]
+    );
+    assert_eq!(preset.window_title.as_deref(), Some("Helix - it's"));
+}
+
+#[test]
+fn test_launcher_preset_wsl() {
+    let preset = Launcher::Wsl.preset("Helix", "hx", Some("Ubuntu 22.04"), true);
+    assert_eq!(
+        split_command(&preset.command).unwrap(),
+        ["wsl.exe", "-d", "Ubuntu 22.04", "--cd", "{project}", "hx"]
+    );
+    let preset = Launcher::Wsl.preset("Helix", "hx", None, false);
+    assert_eq!(preset.command, "wsl.exe hx");
+}
+
+#[test]
+fn test_launcher_preset_helix_program() {
+    let preset = Launcher::Alacritty.preset("Helix", r"C:\Program Files\Helix\hx.exe", None, false);
+    assert_eq!(
+        split_command(&preset.command).unwrap(),
+        [
+            "alacritty",
+            "--title",
+            "Helix",
+            "-e",
+            r"C:\Program Files\Helix\hx.exe"
+        ]
+    );
+    let preset = Launcher::Conhost.preset("Helix", "helix", None, false);
+    assert_eq!(preset.command, "conhost.exe helix");
+}
+
+#[test]
+fn test_program_name() {
+    assert_eq!(program_name("hx"), "hx");
+    assert_eq!(program_name(r"C:\Helix\hx.exe"), "hx");
+    assert_eq!(program_name("/usr/local/bin/helix"), "helix");
+}
+
+#[test]
+fn test_launcher_has_title() {
+    for launcher in [
+        Launcher::WindowsTerminal,
+        Launcher::Alacritty,
+        Launcher::Kitty,
+    ] {
+        assert!(launcher.has_title());
+        assert!(launcher
+            .preset("Helix", "hx", None, false)
+            .window_title
+            .is_some());
+    }
+    for launcher in [Launcher::Wezterm, Launcher::Conhost, Launcher::Wsl] {
+        assert!(!launcher.has_title());
+    }
+}
+
+#[test]
+fn test_helix_args() {
+    assert_eq!(
+        helix_args(Some("C:/my game"), Some(("C:/my game/a.gd", 12, 1))),
+        ["-w", "C:/my game", "C:/my game/a.gd:12:1"]
+    );
+    assert_eq!(helix_args(None, Some(("a.gd", 1, 5))), ["a.gd:1:5"]);
+    assert!(helix_args(None, None).is_empty());
+}
@@ -1,3 +1,20 @@
+pub mod backend;
+pub mod cli;
+pub mod command;
+pub mod config;
+pub mod daemon;
+pub mod desktop;
 pub mod error;
+pub mod godot;
 pub mod keyboard_macro;
+pub mod launcher;
+pub mod location;
+pub mod matcher;
+pub mod path;
+pub mod project;
+pub mod rank;
+pub mod runner;
+pub mod state;
+#[cfg(all(windows, feature = "windows"))]
 pub mod window;
+pub mod window_info;
@@ -0,0 +1,176 @@
+#[cfg(test)]
+mod test;
+
+/// File to open and where to put the cursor in it.
+/// Line and column are 1-based.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    pub path: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    /// Last line of a selected range.
+    pub end_line: Option<u32>,
+}
+
+impl Location {
+    pub fn new(path: String) -> Self {
+        Location {
+            path,
+            ..Default::default()
+        }
+    }
+
+    /// Parse location given in a single argument: `path:line:col`, `path:line`,
+    /// `path(line,col)` (MSBuild), `path(line)` or a `file://` URI.
+    /// Line and column are converted from `line_base` and `column_base` to 1-based.
+    pub fn parse(arg: &str, line_base: u32, column_base: u32) -> Location {
+        if let Some(location) = Location::parse_file_uri(arg) {
+            return location;
+        }
+        let (path, line, column) = split_msbuild_position(arg)
+            .or_else(|| split_colon_position(arg))
+            .unwrap_or((arg, None, None));
+        Location {
+            path: path.to_owned(),
+            line: line.map(|line| to_one_based(line, line_base)),
+            column: column.map(|column| to_one_based(column, column_base)),
+            end_line: None,
+        }
+    }
+
+    /// Parse `file://` URI with optional `#L12`, `#L12C5` or `#L12-L20` fragment.
+    /// Returns `None` if `uri` is not a `file://` URI.
+    pub fn parse_file_uri(uri: &str) -> Option<Location> {
+        let rest = strip_prefix_ignore_case(uri, "file://")?;
+        let (rest, fragment) = match rest.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (rest, None),
+        };
+        let rest = rest.split_once('?').map_or(rest, |(rest, _query)| rest);
+        let (host, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, ""),
+        };
+        let path = percent_decode(path);
+        let path = match host {
+            "" | "localhost" => match path.strip_prefix('/') {
+                // `file:///C:/...`
+                Some(drive_path) if crate::path::split_drive(drive_path).is_some() => {
+                    drive_path.to_owned()
+                }
+                _ => path,
+            },
+            // `file://server/share/...` is a UNC path.
+            host => format!("//{}{path}", percent_decode(host)),
+        };
+
+        let mut location = Location::new(path);
+        if let Some((line, column, end_line)) = fragment.and_then(parse_line_fragment) {
+            location.line = Some(line);
+            location.column = column;
+            location.end_line = end_line;
+        }
+        Some(location)
+    }
+}
+
+/// Convert line or column number counted from `base` to 1-based.
+pub fn to_one_based(value: u32, base: u32) -> u32 {
+    value.saturating_sub(base) + 1
+}
+
+type SplitPosition<'a> = (&'a str, Option<u32>, Option<u32>);
+
+/// Split `path(line,col)` or `path(line)`.
+fn split_msbuild_position(arg: &str) -> Option<SplitPosition<'_>> {
+    let (path, position) = arg.strip_suffix(')')?.rsplit_once('(')?;
+    if path.is_empty() {
+        return None;
+    }
+    match position.split_once(',') {
+        Some((line, column)) => Some((
+            path,
+            Some(line.trim().parse().ok()?),
+            Some(column.trim().parse().ok()?),
+        )),
+        None => Some((path, Some(position.trim().parse().ok()?), None)),
+    }
+}
+
+/// Split `path:line:col` or `path:line`.
+/// A drive letter alone is never taken as the path (`C:12` is a relative path on drive C).
+fn split_colon_position(arg: &str) -> Option<SplitPosition<'_>> {
+    fn number(s: &str) -> Option<u32> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        s.parse().ok()
+    }
+    fn is_path(s: &str) -> bool {
+        let is_drive = s.len() == 1 && s.as_bytes()[0].is_ascii_alphabetic();
+        !s.is_empty() && !is_drive
+    }
+
+    let (rest, last) = arg.rsplit_once(':')?;
+    let last = number(last)?;
+    if let Some((path, line)) = rest.rsplit_once(':') {
+        if let Some(line) = number(line).filter(|_| is_path(path)) {
+            return Some((path, Some(line), Some(last)));
+        }
+    }
+    is_path(rest).then_some((rest, Some(last), None))
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = s.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &s[prefix.len()..])
+}
+
+/// Parse `L12`, `L12C5`, `L12-L20` and `L12C5-L20C1` fragments.
+fn parse_line_fragment(fragment: &str) -> Option<(u32, Option<u32>, Option<u32>)> {
+    fn line_column(s: &str) -> Option<(u32, Option<u32>)> {
+        let s = s.strip_prefix(['L', 'l']).unwrap_or(s);
+        match s.split_once(['C', 'c']) {
+            Some((line, column)) => Some((line.parse().ok()?, Some(column.parse().ok()?))),
+            None => Some((s.parse().ok()?, None)),
+        }
+    }
+
+    if !fragment.starts_with(['L', 'l']) {
+        return None;
+    }
+    match fragment.split_once('-') {
+        Some((start, end)) => {
+            let (line, column) = line_column(start)?;
+            let (end_line, _) = line_column(end)?;
+            Some((line, column, Some(end_line)))
+        }
+        None => {
+            let (line, column) = line_column(fragment)?;
+            Some((line, column, None))
+        }
+    }
+}
+
+/// Decode `%XX` escapes. Invalid escapes are kept as is.
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(byte) = hex {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
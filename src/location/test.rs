@@ -0,0 +1,87 @@
+use super::{percent_decode, to_one_based, Location};
+
+#[test]
+fn test_percent_decode() {
+    assert_eq!(percent_decode("a%20b.gd"), "a b.gd");
+    assert_eq!(percent_decode("%E0%B8%81.gd"), "ก.gd");
+    assert_eq!(percent_decode("100%.gd"), "100%.gd");
+    assert_eq!(percent_decode("%zz%2"), "%zz%2");
+}
+
+#[test]
+fn test_parse_file_uri() {
+    let location = Location::parse_file_uri("file:///C:/Users/USER/a%20b.gd").unwrap();
+    assert_eq!(location, Location::new("C:/Users/USER/a b.gd".to_owned()));
+
+    let location = Location::parse_file_uri("FILE://localhost/home/user/a.gd").unwrap();
+    assert_eq!(location.path, "/home/user/a.gd");
+
+    let location = Location::parse_file_uri("file://server/share/a.gd").unwrap();
+    assert_eq!(location.path, "//server/share/a.gd");
+
+    assert_eq!(Location::parse_file_uri("C:/Users/USER/a.gd"), None);
+    assert_eq!(Location::parse_file_uri("res://a.gd"), None);
+}
+
+#[test]
+fn test_parse_file_uri_fragment() {
+    let location = Location::parse_file_uri("file:///C:/a.gd#L12").unwrap();
+    assert_eq!(
+        (location.line, location.column, location.end_line),
+        (Some(12), None, None)
+    );
+
+    let location = Location::parse_file_uri("file:///C:/a.gd#L12C5").unwrap();
+    assert_eq!(
+        (location.line, location.column, location.end_line),
+        (Some(12), Some(5), None)
+    );
+
+    let location = Location::parse_file_uri("file:///C:/a.gd#L12-L20").unwrap();
+    assert_eq!(
+        (location.line, location.column, location.end_line),
+        (Some(12), None, Some(20))
+    );
+
+    let location = Location::parse_file_uri("file:///C:/a.gd#section").unwrap();
+    assert_eq!(location, Location::new("C:/a.gd".to_owned()));
+}
+
+#[test]
+fn test_to_one_based() {
+    assert_eq!(to_one_based(0, 0), 1);
+    assert_eq!(to_one_based(12, 0), 13);
+    assert_eq!(to_one_based(12, 1), 12);
+    assert_eq!(to_one_based(0, 1), 1);
+}
+
+#[test]
+fn test_parse_location() {
+    fn parse(arg: &str) -> (String, Option<u32>, Option<u32>) {
+        let location = Location::parse(arg, 1, 1);
+        (location.path, location.line, location.column)
+    }
+    let path = |p: &str| p.to_owned();
+
+    assert_eq!(parse("C:/a.gd"), (path("C:/a.gd"), None, None));
+    assert_eq!(parse("C:/a.gd:12"), (path("C:/a.gd"), Some(12), None));
+    assert_eq!(
+        parse("C:\\a.gd:12:5"),
+        (path("C:\\a.gd"), Some(12), Some(5))
+    );
+    assert_eq!(
+        parse("src/a.rs(12,5)"),
+        (path("src/a.rs"), Some(12), Some(5))
+    );
+    assert_eq!(parse("src/a.rs(12)"), (path("src/a.rs"), Some(12), None));
+    assert_eq!(parse("res://a.gd:3"), (path("res://a.gd"), Some(3), None));
+    assert_eq!(parse("C:12"), (path("C:12"), None, None));
+    assert_eq!(parse("C:12:3"), (path("C:12"), Some(3), None));
+    assert_eq!(parse("a.gd:x"), (path("a.gd:x"), None, None));
+    assert_eq!(parse("a (copy).gd"), (path("a (copy).gd"), None, None));
+
+    let location = Location::parse("a.gd:0:0", 0, 0);
+    assert_eq!((location.line, location.column), (Some(1), Some(1)));
+    let location = Location::parse("file:///C:/a.gd#L4", 0, 0);
+    assert_eq!((location.path, location.line), (path("C:/a.gd"), Some(4)));
+}
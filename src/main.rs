@@ -1,226 +1,128 @@
-use std::borrow::Cow;
-
 use clap::Parser;
 use helix_win_runner::{
+    cli::Args,
+    config::Config,
+    daemon::{self, Daemon},
     error::{Error, Result},
-    keyboard_macro::{self, sleep},
-    window::get_windows,
+    rank::Pick,
+    runner::{run, run_request, Output},
+    state::State,
 };
 
-#[cfg(test)]
-mod test;
-
-/// Program to run Helix from external source.
-#[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
-struct Args {
-    /// [DEPRECATED] Command to execute when no window found.
-    /// If not provided, only search will be done.
-    ///
-    /// DEPRECATED: use `run` flag.
-    #[arg(short = 'e', long, value_name = "PATH")]
-    execute_path: Option<String>,
-
-    /// [DEPRECATED] Time to wait after executing `execute_path`.
-    /// Does nothing if not execute.
-    ///
-    /// DEPRECATED: use `run_wait` flag.
-    #[arg(short = 'w', long, value_name = "SECS")]
-    execute_wait: Option<f64>,
-
-    /// Search for window containing this string in the title.
-    /// Atleast one of `window_title` or `window_process_name` must provided.
-    #[arg(short = 't', long, value_name = "STRING")]
-    window_title: Option<String>,
-    /// Search for window containing this string in the process name.
-    /// Atleast one of `window_title` or `window_process_name` must provided.
-    #[arg(short = 'n', long, value_name = "STRING")]
-    window_process_name: Option<String>,
-
-    /// Project path for helix to change directory to.
-    /// Will only be done when executed `execute_path`.
-    #[arg(short = 'p', long, value_name = "PATH")]
-    project_path: Option<String>,
-    /// File path for helix to open.
-    #[arg(short = 'f', long, value_name = "PATH")]
-    file_path: Option<String>,
-    /// Line number in the file for helix to open.
-    #[arg(short = 'l', long, value_name = "NUM")]
-    line: Option<u32>,
-    /// Column number in the file for helix to open.
-    #[arg(short = 'c', long, value_name = "NUM")]
-    column: Option<u32>,
-
-    /// Option to reduce amount of time when writing full file path.
-    /// Only availiable when project path is provided.
-    #[arg(short = 'r', long)]
-    relative: bool,
-
-    /// List availiable windows.
-    /// Format like so: [<process name>] <window title>
-    #[arg(long = "list")]
-    list_windows: bool,
-
-    /// Search through all windows.
-    /// Normally, process's name that contain "Default IME" or "MSCTFIME UI" are ignored by default;
-    /// add this flag to include them.
-    #[arg(long = "all")]
-    all: bool,
-
-    /// Support for using Helix in WSL with application outside WSL
-    /// All it do currently is convert Windows path to Linux path
-    #[arg(long)]
-    wsl: bool,
-
-    /// Use clipboard instead of keyboard macros to speed up the process.
-    /// Assuming Ctrl+V works.
-    /// This will not replace your current clipboard!
-    #[arg(long)]
-    clipboard: bool,
-
-    /// Use no macro when a command is ran
-    #[arg(long)]
-    no_init_macro: bool,
-
-    /// Command to run when no window is found.
-    /// If not provided, only search will be done.
-    #[arg(long = "run", value_name = "CMD")]
-    run_command: Option<Vec<String>>,
-
-    /// After running a command, wait for a bit before running a macro.
-    ///
-    /// `no-launch-macro` flag disable this entirely
-    #[arg(long = "run-wait", value_name = "SECS")]
-    run_command_and_wait: Option<f64>,
-}
-
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    let is_incomplete_arg = matches!(
-        (&args.window_title, &args.window_process_name),
-        (None, None)
-    );
-
-    if args.list_windows {
-        let res = list_windows(args.all);
-        if is_incomplete_arg {
-            return res;
+    if args.daemon {
+        return run_daemon(&args);
+    }
+    // Doctor, listing and the pick prompt are for this terminal.
+    if args.use_daemon && !args.doctor && !args.list_windows && args.pick != Pick::Ask {
+        let address = args
+            .daemon_address
+            .clone()
+            .unwrap_or_else(daemon::default_address);
+        let request = daemon::Request {
+            args: std::env::args_os()
+                .skip(1)
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+            working_directory: std::env::current_dir().ok(),
+        };
+        match daemon::send(&address, &request) {
+            Ok(response) => {
+                print!("{}", response.stdout);
+                eprint!("{}", response.stderr);
+                return match response.error {
+                    Some(e) => Err(Error::Daemon(e)),
+                    None => Ok(()),
+                };
+            }
+            Err(e) if daemon::is_not_running(&e) => {
+                if args.verbose {
+                    println!("No daemon on {address}, running directly");
+                }
+            }
+            Err(e) => return Err(e),
         }
     }
 
-    if is_incomplete_arg {
-        return Err(Error::IncompleteSearchArgument);
+    let config = Config::load(args.config.as_deref());
+    if args.doctor {
+        doctor(&args, config);
+        return Ok(());
     }
+    let config = match config {
+        // Listing is how a broken setup gets fixed, so it works without the config.
+        Err(e) if args.list_windows => {
+            eprintln!("Failed to load config, listing with the default one: {e}");
+            Config::default()
+        }
+        config => config?,
+    };
+    run(args, &config, &mut Output::terminal())
+}
 
-    let window_title = args.window_title.unwrap_or_else(|| "".to_owned());
-    let window_process_name = args.window_process_name.unwrap_or_else(|| "".to_owned());
-
-    let res = focus_window(args.all, &window_title, &window_process_name);
-    let mut is_change_directory = false;
-    let run_command_args = args
-        .run_command
-        .or_else(|| args.execute_path.map(|p| vec![p]));
-    match (res, run_command_args) {
-        (Err(Error::WindowNotFound), Some(run_command_args)) => {
-            run_command(run_command_args)?;
-            if let Some(wait) = args.run_command_and_wait.or(args.execute_wait) {
-                sleep(wait);
+/// Listen for requests sent with `use_daemon` and run them one at a time,
+/// until the daemon can't accept clients anymore.
+/// Config is loaded once and kept between requests.
+fn run_daemon(args: &Args) -> Result<()> {
+    let config = Config::load(args.config.as_deref())?;
+    let address = args
+        .daemon_address
+        .clone()
+        .unwrap_or_else(daemon::default_address);
+    let mut daemon = Daemon::bind(&address)?;
+    println!("Daemon listening on {address}");
+    loop {
+        let client = daemon.accept()?;
+        let res = client.serve(|request| {
+            if args.verbose {
+                println!("Request {:?}", request.args);
             }
-            focus_window(args.all, &window_title, &window_process_name)?;
-            if args.no_init_macro {
-                return Ok(());
+            let mut out = Output::captured();
+            let res = run_request(request, &config, &mut out);
+            if let Err(e) = &res {
+                eprintln!("Request failed: {e}");
             }
-            if let Some(project_path) = &args.project_path {
-                let project_path = if args.wsl {
-                    Cow::Owned(window_path_to_wsl(project_path))
-                } else {
-                    Cow::Borrowed(project_path)
-                };
-                keyboard_macro::helix_change_directory(&project_path, args.clipboard);
-                is_change_directory = true;
+            daemon::Response {
+                error: res.err().map(|e| e.to_string()),
+                stdout: out.stdout,
+                stderr: out.stderr,
             }
+        });
+        // Only this client is affected, the next one is served as usual.
+        if let Err(e) = res {
+            eprintln!("Failed to answer request: {e}");
         }
-        (Err(e), _) => return Err(e),
-        _ => {}
     }
+}
 
-    sleep(0.1);
-
-    if let Some(file_path) = args.file_path {
-        if is_change_directory {
-            sleep(0.1);
+fn doctor(args: &Args, config: Result<Config>) {
+    let path = args.config.clone().or_else(Config::default_path);
+    match path {
+        Some(path) if path.is_file() => println!("Config file: {}", path.display()),
+        Some(path) => println!("Config file: {} (not found)", path.display()),
+        None => println!("Config file: none (no config directory)"),
+    }
+    println!();
+    match config {
+        Ok(config) => {
+            println!("Effective config:");
+            print!("{}", config.to_toml());
         }
-        let file_path = match (args.relative, args.project_path) {
-            (true, Some(project_path)) if file_path[..project_path.len()] == project_path => {
-                // add one to remove the `/`.
-                // It means absolute path with that on
-                &file_path[project_path.len() + 1..]
-            }
-            _ => &file_path[..],
-        };
-        let file_path = if args.wsl {
-            Cow::Owned(window_path_to_wsl(file_path))
-        } else {
-            Cow::Borrowed(file_path)
-        };
-        let line = args.line.unwrap_or(0) + 1;
-        let column = args.column.unwrap_or(0) + 1;
-        keyboard_macro::helix_open_file(&file_path, line, column, args.clipboard);
+        Err(e) => println!("Failed to load config: {e}"),
     }
-
-    Ok(())
-}
-
-fn run_command(args: Vec<String>) -> Result<()> {
-    use std::process::Command;
-    Command::new("cmd").arg("/C").args(args).spawn()?;
-    Ok(())
-}
-
-fn list_windows(all: bool) -> Result<()> {
-    get_windows(all)?
-        .into_iter()
-        .for_each(|(name, title, _window)| println!("[{name}] {title}"));
-    Ok(())
-}
-
-fn focus_window(
-    all: bool,
-    search_window_title: &str,
-    search_window_process_name: &str,
-) -> Result<()> {
-    use helix_win_runner::window::{attach_thread_input, get_current_thread_id};
-    let mut windows = get_windows(all)?;
-    windows.sort_by(|a, b| a.0.cmp(&b.0));
-    let window = windows.into_iter().find(|(name, title, _window)| {
-        name.contains(search_window_process_name) && title.contains(search_window_title)
-    });
-    let Some((name, title, window)) = window else {
-        return Err(Error::WindowNotFound);
-    };
-
-    println!("Focusing [{name}] {title}");
-
-    let current_thread_id = get_current_thread_id();
-    attach_thread_input(window.thread_id(), current_thread_id, true)?;
-
-    window.pop_focus()?;
-    window.pop_focus()?;
-    window.pop_focus()?;
-
-    attach_thread_input(window.thread_id(), current_thread_id, false)?;
-    Ok(())
-}
-
-fn window_path_to_wsl(path: &str) -> String {
-    let path = path.replace('\\', "/");
-    let is_absolute = &path[1..=2] == ":/";
-    if is_absolute {
-        let drive = path.chars().next().unwrap().to_lowercase();
-        let path = &path[3..];
-        format!("/mnt/{}/{}", drive, path)
-    } else {
-        path
+    println!();
+    match State::default_path() {
+        _ if args.no_state => println!("State file: disabled"),
+        Some(path) => {
+            let state = State::load(&path);
+            println!(
+                "State file: {} ({} launched instances)",
+                path.display(),
+                state.instances.len()
+            );
+        }
+        None => println!("State file: none (no local data directory)"),
     }
 }
@@ -0,0 +1,95 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::error::{Error, Result};
+
+#[cfg(test)]
+mod test;
+
+/// How a search string is compared against window title and process name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum MatchMode {
+    /// Contains the string.
+    #[default]
+    Substring,
+    /// Matches the regular expression anywhere.
+    Regex,
+    /// Matches the whole string with `*`, `?` and `[...]` wildcards.
+    Glob,
+    /// Equals the string.
+    Exact,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    regex: Regex,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str, mode: MatchMode, case_sensitive: bool) -> Result<Pattern> {
+        let source = match mode {
+            MatchMode::Substring => regex::escape(pattern),
+            MatchMode::Regex => pattern.to_owned(),
+            MatchMode::Glob => glob_to_regex(pattern),
+            MatchMode::Exact => format!("^{}$", regex::escape(pattern)),
+        };
+        let regex = RegexBuilder::new(&source)
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map_err(|e| Error::InvalidPattern(e.to_string()))?;
+        Ok(Pattern { regex })
+    }
+
+    pub fn is_match(&self, s: &str) -> bool {
+        self.regex.is_match(s)
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '[' => {
+                let class: String = chars.by_ref().take_while(|c| *c != ']').collect();
+                let (negate, class) = match class.strip_prefix('!') {
+                    Some(class) => ("^", class),
+                    None => ("", &class[..]),
+                };
+                regex.push('[');
+                regex.push_str(negate);
+                regex.push_str(&class.replace('\\', "\\\\").replace('[', "\\["));
+                regex.push(']');
+            }
+            c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// Decide which windows are searched for, using title and process name.
+#[derive(Debug, Clone, Default)]
+pub struct WindowMatcher {
+    pub title: Option<Pattern>,
+    pub process_name: Option<Pattern>,
+    pub exclude_title: Vec<Pattern>,
+    pub exclude_process_name: Vec<Pattern>,
+    /// Project tag the title must contain, see [`crate::project::ProjectTag`].
+    pub project_tag: Option<Pattern>,
+}
+
+impl WindowMatcher {
+    pub fn is_match(&self, process_name: &str, title: &str) -> bool {
+        let is_included =
+            |pattern: &Option<Pattern>, s: &str| pattern.iter().all(|pattern| pattern.is_match(s));
+        let is_excluded =
+            |patterns: &[Pattern], s: &str| patterns.iter().any(|pattern| pattern.is_match(s));
+        is_included(&self.title, title)
+            && is_included(&self.project_tag, title)
+            && is_included(&self.process_name, process_name)
+            && !is_excluded(&self.exclude_title, title)
+            && !is_excluded(&self.exclude_process_name, process_name)
+    }
+}
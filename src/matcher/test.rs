@@ -0,0 +1,65 @@
+use super::{MatchMode, Pattern, WindowMatcher};
+
+fn is_match(pattern: &str, mode: MatchMode, case_sensitive: bool, s: &str) -> bool {
+    Pattern::new(pattern, mode, case_sensitive)
+        .unwrap()
+        .is_match(s)
+}
+
+#[test]
+fn test_pattern() {
+    use MatchMode::*;
+    assert!(is_match("Helix", Substring, true, "Helix docs - Firefox"));
+    assert!(!is_match("helix", Substring, true, "Helix"));
+    assert!(is_match("helix", Substring, false, "Helix"));
+
+    assert!(is_match("Helix", Exact, true, "Helix"));
+    assert!(!is_match("Helix", Exact, true, "Helix docs"));
+    assert!(is_match("helix", Exact, false, "HELIX"));
+
+    assert!(is_match("^hx( |$)", Regex, true, "hx project"));
+    assert!(!is_match("^hx( |$)", Regex, true, "hxx"));
+
+    assert!(is_match("*Terminal.exe", Glob, true, "WindowsTerminal.exe"));
+    assert!(!is_match(
+        "*Terminal.exe",
+        Glob,
+        true,
+        "WindowsTerminalXexe"
+    ));
+    assert!(is_match("hx - ?", Glob, true, "hx - a"));
+    assert!(is_match("[!a]x", Glob, true, "hx"));
+    assert!(!is_match("[!h]x", Glob, true, "hx"));
+    assert!(!is_match("Helix", Glob, true, "Helix docs"));
+
+    assert!(Pattern::new("(", Regex, true).is_err());
+    assert!(Pattern::new("(", Substring, true).is_ok());
+}
+
+#[test]
+fn test_window_matcher() {
+    let pattern = |s: &str| Pattern::new(s, MatchMode::Substring, true).unwrap();
+    let matcher = WindowMatcher {
+        title: Some(pattern("Helix")),
+        process_name: Some(pattern("WindowsTerminal")),
+        exclude_title: vec![pattern("docs")],
+        exclude_process_name: vec![],
+        project_tag: None,
+    };
+    assert!(matcher.is_match("WindowsTerminal.exe", "Helix"));
+    assert!(!matcher.is_match("firefox.exe", "Helix"));
+    assert!(!matcher.is_match("WindowsTerminal.exe", "Helix docs"));
+
+    assert!(WindowMatcher::default().is_match("any.exe", "any"));
+}
+
+#[test]
+fn test_window_matcher_project_tag() {
+    let matcher = WindowMatcher {
+        title: Some(Pattern::new("Helix", MatchMode::Substring, true).unwrap()),
+        project_tag: Some(Pattern::new("my_game", MatchMode::Substring, false).unwrap()),
+        ..Default::default()
+    };
+    assert!(matcher.is_match("WindowsTerminal.exe", "Helix - My_Game"));
+    assert!(!matcher.is_match("WindowsTerminal.exe", "Helix - other_game"));
+}
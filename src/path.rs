@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod test;
+
+/// Path style used by the environment Helix runs in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum PathStyle {
+    /// `C:\Users\...`, paths are given as is.
+    #[default]
+    Windows,
+    /// `/mnt/c/Users/...`
+    Wsl,
+    /// `/c/Users/...`, used by MSYS2 and Git Bash.
+    Msys,
+    /// `/cygdrive/c/Users/...`
+    Cygwin,
+}
+
+impl PathStyle {
+    /// Convert a Windows path to this style.
+    /// Relative paths only have their separators converted.
+    pub fn convert(self, path: &str) -> String {
+        let prefix = match self {
+            PathStyle::Windows => return path.to_owned(),
+            PathStyle::Wsl => "/mnt/",
+            PathStyle::Msys => "/",
+            PathStyle::Cygwin => "/cygdrive/",
+        };
+        let path = path.replace('\\', "/");
+        match split_drive(&path) {
+            Some((drive, rest)) => {
+                let drive = drive.to_ascii_lowercase();
+                let rest = rest.trim_start_matches('/');
+                format!("{prefix}{drive}/{rest}")
+            }
+            None => path,
+        }
+    }
+}
+
+/// Path split into its root and components, used to compare paths that came from
+/// different sources (Godot sends `/`, Windows uses `\`).
+#[derive(Debug, PartialEq, Eq)]
+struct NormalizedPath<'a> {
+    root: Option<String>,
+    components: Vec<&'a str>,
+}
+
+impl<'a> NormalizedPath<'a> {
+    fn new(path: &'a str) -> Self {
+        let mut rest = path;
+        let mut root = None;
+        if let Some((drive, after)) = split_drive(rest) {
+            root = Some(format!("{}:", drive.to_ascii_lowercase()));
+            rest = after;
+        } else if rest.starts_with(['/', '\\']) {
+            root = Some("/".to_owned());
+        }
+        let components = rest
+            .split(['/', '\\'])
+            .filter(|component| !component.is_empty() && *component != ".")
+            .collect();
+        NormalizedPath { root, components }
+    }
+}
+
+/// Normalize `path` for comparison: `/` separators, lowercase drive letter,
+/// no empty or `.` components and no trailing separator.
+pub fn normalize_path(path: &str) -> String {
+    let path = NormalizedPath::new(path);
+    let components = path.components.join("/");
+    match path.root.as_deref() {
+        Some("/") => format!("/{components}"),
+        Some(root) => format!("{root}/{components}"),
+        None => components,
+    }
+}
+
+/// Split `C:\...` or `C:/...` into the drive letter and the rest.
+pub(crate) fn split_drive(path: &str) -> Option<(char, &str)> {
+    let mut chars = path.chars();
+    let drive = chars.next().filter(char::is_ascii_alphabetic)?;
+    if chars.next() != Some(':') {
+        return None;
+    }
+    Some((drive, &path[2..]))
+}
+
+/// Compute `file` relative to `project`, using `/` as separator.
+///
+/// Separators and drive letter case are ignored when comparing.
+/// Returns `None` when the paths are on different roots, when `file` is the project itself,
+/// or when `file` is outside of `project` and `allow_parent` is false.
+/// Otherwise with `allow_parent`, files outside of the project are given as `../` paths.
+pub fn relative_path(project: &str, file: &str, allow_parent: bool) -> Option<String> {
+    let project = NormalizedPath::new(project);
+    let file = NormalizedPath::new(file);
+    if project.root != file.root {
+        return None;
+    }
+
+    let common = project
+        .components
+        .iter()
+        .zip(&file.components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let parents = project.components.len() - common;
+    if parents > 0 && !allow_parent {
+        return None;
+    }
+    if common == file.components.len() && parents == 0 {
+        return None;
+    }
+
+    let components: Vec<&str> = std::iter::repeat_n("..", parents)
+        .chain(file.components[common..].iter().copied())
+        .collect();
+    Some(components.join("/"))
+}
+
+/// Rule to replace the beginning of a path, configured by `path_map` in the config.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PathMapping {
+    pub from: String,
+    pub to: String,
+}
+
+/// Replace the longest matching prefix of `path` using `mappings`.
+/// When multiple rules match the same length, the first one wins.
+///
+/// Prefixes are matched by whole components, ignoring separators and drive letter case.
+/// The rest of the path is joined with `\` if the replacement contains one, otherwise `/`.
+pub fn map_path_prefix(mappings: &[PathMapping], path: &str) -> Option<String> {
+    let normalized_path = NormalizedPath::new(path);
+    let (mapping, matched) = mappings
+        .iter()
+        .filter_map(|mapping| {
+            let from = NormalizedPath::new(&mapping.from);
+            let is_prefix = from.root == normalized_path.root
+                && normalized_path.components.starts_with(&from.components);
+            is_prefix.then_some((mapping, from.components.len()))
+        })
+        .fold(
+            None,
+            |best: Option<(&PathMapping, usize)>, (mapping, len)| match best {
+                Some((_, best_len)) if best_len >= len => best,
+                _ => Some((mapping, len)),
+            },
+        )?;
+
+    let separator = if mapping.to.contains('\\') { '\\' } else { '/' };
+    let mut mapped = mapping.to.trim_end_matches(['/', '\\']).to_owned();
+    for component in &normalized_path.components[matched..] {
+        mapped.push(separator);
+        mapped.push_str(component);
+    }
+    if mapped.is_empty() {
+        mapped.push(separator);
+    }
+    Some(mapped)
+}
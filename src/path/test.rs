@@ -0,0 +1,136 @@
+use super::{map_path_prefix, normalize_path, relative_path, PathMapping, PathStyle};
+
+#[test]
+fn test_window_path_to_wsl() {
+    let path = "C:\\Users\\USER\\OneDrive\\Desktop";
+    let linux_path = PathStyle::Wsl.convert(path);
+    assert_eq!(linux_path, "/mnt/c/Users/USER/OneDrive/Desktop");
+
+    let path = "Users\\USER\\OneDrive\\Desktop";
+    let linux_path = PathStyle::Wsl.convert(path);
+    assert_eq!(linux_path, "Users/USER/OneDrive/Desktop");
+}
+
+#[test]
+fn test_path_style() {
+    let path = "C:/Users/USER/project";
+    assert_eq!(PathStyle::Windows.convert(path), "C:/Users/USER/project");
+    assert_eq!(PathStyle::Msys.convert(path), "/c/Users/USER/project");
+    assert_eq!(
+        PathStyle::Cygwin.convert(path),
+        "/cygdrive/c/Users/USER/project"
+    );
+    assert_eq!(PathStyle::Wsl.convert("D:"), "/mnt/d/");
+    assert_eq!(PathStyle::Msys.convert("a"), "a");
+}
+
+#[test]
+fn test_relative_path() {
+    let project = "C:/Users/USER/project";
+    let file = "C:/Users/USER/project/scripts/player.gd";
+    assert_eq!(
+        relative_path(project, file, false).as_deref(),
+        Some("scripts/player.gd")
+    );
+
+    let project = "c:\\Users\\USER\\project\\";
+    assert_eq!(
+        relative_path(project, file, false).as_deref(),
+        Some("scripts/player.gd")
+    );
+}
+
+#[test]
+fn test_relative_path_not_inside() {
+    let project = "C:/Users/USER/project";
+    assert_eq!(relative_path(project, "C:/a.gd", false), None);
+    assert_eq!(
+        relative_path(project, "D:/Users/USER/project/a.gd", false),
+        None
+    );
+    assert_eq!(
+        relative_path(project, "C:/Users/USER/project2/a.gd", false),
+        None
+    );
+    assert_eq!(relative_path(project, "C:/Users/USER/project", false), None);
+    assert_eq!(
+        relative_path(project, "C:/Users/USER/pröject/a.gd", false),
+        None
+    );
+}
+
+#[test]
+fn test_relative_path_parent() {
+    let project = "C:/Users/USER/project";
+    assert_eq!(
+        relative_path(project, "C:/Users/USER/addons/a.gd", true).as_deref(),
+        Some("../addons/a.gd")
+    );
+    assert_eq!(
+        relative_path(project, "C:/Users/USER/project2/a.gd", true).as_deref(),
+        Some("../project2/a.gd")
+    );
+    assert_eq!(relative_path(project, "D:/a.gd", true), None);
+}
+
+#[test]
+fn test_map_path_prefix() {
+    let mappings = [
+        PathMapping {
+            from: "D:\\work".to_owned(),
+            to: "/workspace".to_owned(),
+        },
+        PathMapping {
+            from: "D:/work/game".to_owned(),
+            to: "/game/".to_owned(),
+        },
+        PathMapping {
+            from: "/mnt/c".to_owned(),
+            to: "\\\\server\\c".to_owned(),
+        },
+    ];
+    assert_eq!(
+        map_path_prefix(&mappings, "d:/work/tool/a.gd").as_deref(),
+        Some("/workspace/tool/a.gd")
+    );
+    assert_eq!(
+        map_path_prefix(&mappings, "D:\\work\\game\\a.gd").as_deref(),
+        Some("/game/a.gd")
+    );
+    assert_eq!(
+        map_path_prefix(&mappings, "D:\\work").as_deref(),
+        Some("/workspace")
+    );
+    assert_eq!(
+        map_path_prefix(&mappings, "/mnt/c/Users").as_deref(),
+        Some("\\\\server\\c\\Users")
+    );
+    assert_eq!(map_path_prefix(&mappings, "D:/workspace/a.gd"), None);
+    assert_eq!(map_path_prefix(&mappings, "work/a.gd"), None);
+}
+
+#[test]
+fn test_map_path_prefix_order() {
+    let mappings = [
+        PathMapping {
+            from: "D:/work".to_owned(),
+            to: "/first".to_owned(),
+        },
+        PathMapping {
+            from: "d:\\work".to_owned(),
+            to: "/second".to_owned(),
+        },
+    ];
+    assert_eq!(
+        map_path_prefix(&mappings, "D:/work/a.gd").as_deref(),
+        Some("/first/a.gd")
+    );
+}
+
+#[test]
+fn test_normalize_path() {
+    assert_eq!(normalize_path("C:\\Users\\USER\\"), "c:/Users/USER");
+    assert_eq!(normalize_path("c:/Users/./USER"), "c:/Users/USER");
+    assert_eq!(normalize_path("/home//user/"), "/home/user");
+    assert_eq!(normalize_path("a\\b"), "a/b");
+}
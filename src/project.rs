@@ -0,0 +1,60 @@
+use crate::{
+    error::Result,
+    matcher::{MatchMode, Pattern},
+    path::normalize_path,
+};
+
+#[cfg(test)]
+mod test;
+
+/// What identifies a project's Helix window in its title.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProjectTag {
+    /// Name of the project directory.
+    Name,
+    /// Token generated from the project path, see [`title_token`].
+    Token,
+}
+
+impl ProjectTag {
+    pub fn tag(self, project_path: &str) -> Option<String> {
+        match self {
+            ProjectTag::Name => project_name(project_path).map(str::to_owned),
+            ProjectTag::Token => Some(title_token(project_path)),
+        }
+    }
+}
+
+/// Pattern finding `tag` in a title as a whole token, ignoring case,
+/// so a project `game` doesn't take the window of `my_game` or `game2`.
+pub fn tag_pattern(tag: &str) -> Result<Pattern> {
+    // Characters found in directory names, anything else separates the tag from the title.
+    let edge = r"[^\w.-]";
+    let source = format!("(?:^|{edge}){}(?:{edge}|$)", regex::escape(tag));
+    Pattern::new(&source, MatchMode::Regex, false)
+}
+
+/// Last component of the project path.
+pub fn project_name(project_path: &str) -> Option<&str> {
+    project_path
+        .rsplit(['/', '\\'])
+        .find(|component| !component.is_empty())
+}
+
+/// Token to put in the window title of a project's Helix, like `hx-my_game-1a2b3c4d`.
+///
+/// The hash comes from the normalized project path,
+/// so projects with the same directory name get different tokens.
+pub fn title_token(project_path: &str) -> String {
+    let normalized = normalize_path(project_path);
+    // FNV-1a, stable across versions unlike `DefaultHasher`.
+    let hash = normalized.bytes().fold(0x811c9dc5u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    });
+    let name: String = project_name(project_path)
+        .unwrap_or("")
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("hx-{name}-{hash:08x}")
+}
@@ -0,0 +1,55 @@
+use super::{project_name, tag_pattern, title_token, ProjectTag};
+use crate::matcher::{MatchMode, Pattern, WindowMatcher};
+
+#[test]
+fn test_project_name() {
+    assert_eq!(project_name("C:/Users/USER/my_game"), Some("my_game"));
+    assert_eq!(project_name("C:\\Users\\USER\\my_game\\"), Some("my_game"));
+    assert_eq!(project_name(""), None);
+}
+
+#[test]
+fn test_title_token() {
+    let token = title_token("C:/Users/USER/my game");
+    assert!(token.starts_with("hx-my_game-"));
+    assert_eq!(token.len(), "hx-my_game-".len() + 8);
+    assert_eq!(token, title_token("c:\\Users\\USER\\my game\\"));
+    assert_ne!(token, title_token("D:/Users/USER/my game"));
+
+    assert_eq!(
+        ProjectTag::Name.tag("C:/Users/USER/my_game").as_deref(),
+        Some("my_game")
+    );
+    assert_eq!(ProjectTag::Token.tag("C:/a"), Some(title_token("C:/a")));
+}
+
+#[test]
+fn test_tag_pattern() {
+    let matcher = |tag: &str| WindowMatcher {
+        title: Some(Pattern::new("Helix", MatchMode::Substring, true).unwrap()),
+        project_tag: Some(tag_pattern(tag).unwrap()),
+        ..Default::default()
+    };
+    let titles = [
+        "Helix my_game",
+        "Helix game",
+        "Helix game2",
+        "Helix - Game (hx)",
+    ];
+    let matching = |tag: &str| -> Vec<_> {
+        let matcher = matcher(tag);
+        titles
+            .into_iter()
+            .filter(|title| matcher.is_match("kitty", title))
+            .collect()
+    };
+    assert_eq!(matching("game"), ["Helix game", "Helix - Game (hx)"]);
+    assert_eq!(matching("my_game"), ["Helix my_game"]);
+    assert!(matching("my").is_empty());
+
+    let token = title_token("C:/my.game");
+    assert!(tag_pattern(&token)
+        .unwrap()
+        .is_match(&format!("Helix {token}")));
+    assert!(!tag_pattern("my.game").unwrap().is_match("Helix myXgame"));
+}
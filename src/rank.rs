@@ -0,0 +1,138 @@
+use std::fmt;
+
+#[cfg(test)]
+mod test;
+
+/// What to do when multiple windows match the search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Pick {
+    /// First window sorted by process name.
+    First,
+    /// Window with the highest score.
+    #[default]
+    Best,
+    /// Ask which window to use in the terminal.
+    Ask,
+    /// Fail with an error.
+    Fail,
+}
+
+/// Information used to score windows that matched the search.
+#[derive(Debug, Clone, Default)]
+pub struct Ranker {
+    pub title: Option<String>,
+    pub process_name: Option<String>,
+    pub case_sensitive: bool,
+    /// Name of the project directory.
+    pub project_name: Option<String>,
+    /// Process names in order of preference.
+    pub preferred_process_names: Vec<String>,
+}
+
+impl Ranker {
+    /// Score a window, `z_order` is 0 for the topmost window.
+    /// `launched` is whether the runner launched the window for this project.
+    pub fn score(&self, process_name: &str, title: &str, z_order: usize, launched: bool) -> Score {
+        let eq = |a: &str, b: &str| {
+            if self.case_sensitive {
+                a == b
+            } else {
+                a.to_lowercase() == b.to_lowercase()
+            }
+        };
+        let contains = |haystack: &str, needle: &str| {
+            if self.case_sensitive {
+                haystack.contains(needle)
+            } else {
+                haystack.to_lowercase().contains(&needle.to_lowercase())
+            }
+        };
+        Score {
+            launched,
+            exact_title: self.title.as_deref().is_some_and(|s| eq(s, title)),
+            exact_process_name: self
+                .process_name
+                .as_deref()
+                .is_some_and(|s| eq(s, process_name)),
+            project_in_title: self
+                .project_name
+                .as_deref()
+                .is_some_and(|s| !s.is_empty() && contains(title, s)),
+            preferred: self
+                .preferred_process_names
+                .iter()
+                .position(|s| eq(s, process_name)),
+            z_order,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    /// Launched by the runner for the project, see [`crate::state`].
+    pub launched: bool,
+    pub exact_title: bool,
+    pub exact_process_name: bool,
+    pub project_in_title: bool,
+    /// Position in the preferred process names.
+    pub preferred: Option<usize>,
+    /// Position from the top, lower were focused more recently.
+    pub z_order: usize,
+}
+
+impl Score {
+    /// Points from matching criteria, z-order only breaks ties.
+    pub fn points(&self) -> u32 {
+        let mut points = 0;
+        if self.launched {
+            points += 16;
+        }
+        if self.project_in_title {
+            points += 8;
+        }
+        if self.exact_title {
+            points += 4;
+        }
+        if self.exact_process_name {
+            points += 2;
+        }
+        if self.preferred.is_some() {
+            points += 1;
+        }
+        points
+    }
+
+    /// Compare for sorting, better score first.
+    pub fn cmp_best(&self, other: &Score) -> std::cmp::Ordering {
+        other
+            .points()
+            .cmp(&self.points())
+            .then_with(|| {
+                let preferred = |score: &Score| score.preferred.unwrap_or(usize::MAX);
+                preferred(self).cmp(&preferred(other))
+            })
+            .then_with(|| self.z_order.cmp(&other.z_order))
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} points", self.points())?;
+        if self.launched {
+            write!(f, ", launched by runner")?;
+        }
+        if self.project_in_title {
+            write!(f, ", project in title")?;
+        }
+        if self.exact_title {
+            write!(f, ", exact title")?;
+        }
+        if self.exact_process_name {
+            write!(f, ", exact process name")?;
+        }
+        if let Some(preferred) = self.preferred {
+            write!(f, ", preferred process #{}", preferred + 1)?;
+        }
+        write!(f, ", z-order {}", self.z_order)
+    }
+}
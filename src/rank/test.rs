@@ -0,0 +1,53 @@
+use super::Ranker;
+
+#[test]
+fn test_score() {
+    let ranker = Ranker {
+        title: Some("Helix".to_owned()),
+        process_name: Some("WindowsTerminal.exe".to_owned()),
+        case_sensitive: false,
+        project_name: Some("my_game".to_owned()),
+        preferred_process_names: vec![
+            "wezterm-gui.exe".to_owned(),
+            "WindowsTerminal.exe".to_owned(),
+        ],
+    };
+
+    let score = ranker.score("windowsterminal.exe", "helix", 3, false);
+    assert!(score.exact_title && score.exact_process_name && !score.project_in_title);
+    assert_eq!(score.preferred, Some(1));
+    assert_eq!(score.points(), 7);
+
+    let score = ranker.score("WindowsTerminal.exe", "Helix - my_game", 0, false);
+    assert!(!score.exact_title && score.project_in_title);
+    assert_eq!(score.points(), 11);
+
+    let score = ranker.score("cmd.exe", "hx", 5, true);
+    assert!(score.launched);
+    assert_eq!(score.points(), 16);
+}
+
+#[test]
+fn test_score_order() {
+    let ranker = Ranker {
+        title: Some("Helix".to_owned()),
+        case_sensitive: true,
+        project_name: Some("my_game".to_owned()),
+        preferred_process_names: vec![
+            "wezterm-gui.exe".to_owned(),
+            "WindowsTerminal.exe".to_owned(),
+        ],
+        ..Default::default()
+    };
+    let mut windows = [
+        ("firefox.exe", "Helix docs", 0),
+        ("WindowsTerminal.exe", "Helix", 1),
+        ("wezterm-gui.exe", "Helix", 2),
+        ("WindowsTerminal.exe", "Helix", 3),
+        ("WindowsTerminal.exe", "Helix my_game", 4),
+    ]
+    .map(|(process_name, title, z_order)| ranker.score(process_name, title, z_order, false));
+    windows.sort_by(|a, b| a.cmp_best(b));
+    let z_orders = windows.map(|score| score.z_order);
+    assert_eq!(z_orders, [4, 2, 1, 3, 0]);
+}
@@ -0,0 +1,721 @@
+use std::{path::Path, process::Child, time::Instant};
+
+use clap::{Parser, ValueEnum};
+
+#[cfg(all(windows, feature = "windows"))]
+use crate::desktop::windows::WindowsDesktop;
+#[cfg(unix)]
+use crate::desktop::x11::X11;
+use crate::{
+    backend::{kitty::Kitty, tmux::Tmux, wezterm::Wezterm, zellij::Zellij, Backend, BackendKind},
+    cli::Args,
+    command::RunCommand,
+    config::Config,
+    daemon,
+    desktop::{Desktop, WindowSearch},
+    error::{Error, Result},
+    godot,
+    keyboard_macro::{self, sleep, Step},
+    launcher::{self, helix_args, LaunchMode, Launcher},
+    location::{to_one_based, Location},
+    matcher::{Pattern, WindowMatcher},
+    path::{map_path_prefix, relative_path, PathStyle},
+    project::{project_name, tag_pattern, title_token},
+    rank::{Pick, Ranker, Score},
+    state::{Instance, State, WindowIdentity},
+    window_info::{format_window_list, ListFormat, WindowInfo},
+};
+
+#[cfg(test)]
+mod test;
+
+/// Where messages of a run go, the terminal or a daemon client.
+#[derive(Debug, Default)]
+pub struct Output {
+    /// Keep messages in `stdout` and `stderr` instead of printing them.
+    pub capture: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Output {
+    pub fn terminal() -> Output {
+        Output::default()
+    }
+
+    pub fn captured() -> Output {
+        Output {
+            capture: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn print(&mut self, text: &str) {
+        if self.capture {
+            self.stdout.push_str(text);
+        } else {
+            print!("{text}");
+        }
+    }
+
+    pub fn println(&mut self, line: String) {
+        self.print(&(line + "\n"));
+    }
+
+    /// Print a warning to standard error.
+    pub fn eprintln(&mut self, line: String) {
+        if self.capture {
+            self.stderr.push_str(&line);
+            self.stderr.push('\n');
+        } else {
+            eprintln!("{line}");
+        }
+    }
+}
+
+/// Run a request sent to the daemon, from the client's working directory.
+///
+/// The daemon's config is used, and picking a window needs the client's terminal,
+/// so requests giving `config` or `pick ask` are refused.
+pub fn run_request(request: daemon::Request, config: &Config, out: &mut Output) -> Result<()> {
+    let args =
+        Args::try_parse_from(std::iter::once("helix-win-runner".to_owned()).chain(request.args))
+            .map_err(|e| Error::Daemon(e.to_string()))?;
+    if args.config.is_some() {
+        return Err(Error::Daemon(
+            "`config` can't be given per request, restart the daemon with it".to_owned(),
+        ));
+    }
+    if args.pick == Pick::Ask {
+        return Err(Error::Daemon(
+            "`pick ask` needs a terminal, run it without the daemon".to_owned(),
+        ));
+    }
+    if let Some(working_directory) = &request.working_directory {
+        // Would be taken from the daemon's directory instead of the client's.
+        if working_directory.is_relative() {
+            return Err(Error::Daemon(format!(
+                "working directory `{}` is not absolute",
+                working_directory.display()
+            )));
+        }
+        std::env::set_current_dir(working_directory)?;
+    }
+    run(args, config, out)
+}
+
+/// Search for Helix and open the file.
+pub fn run(mut args: Args, config: &Config, out: &mut Output) -> Result<()> {
+    let mut desktop = open_desktop(&args)?;
+    // Launched instances are only tracked for desktop windows.
+    let state_path = match &desktop {
+        Some(_) if !args.no_state => State::default_path(),
+        _ => None,
+    };
+    // Read for every run, other runs may have changed it since, even while a daemon runs.
+    let mut state = state_path.as_deref().map(State::load).unwrap_or_default();
+    if let (Some(state_path), Some(desktop)) = (&state_path, &desktop) {
+        if !state.instances.is_empty() && state.prune(&desktop.windows()?) {
+            save_state(&state, state_path, out);
+        }
+    }
+    let path_style = if args.wsl {
+        PathStyle::Wsl
+    } else {
+        args.path_style
+    };
+    let convert_path = |path: &str| {
+        let path = path_style.convert(path);
+        map_path_prefix(&config.path_map, &path).unwrap_or(path)
+    };
+
+    let project_tag = match (args.per_project, &args.project_path) {
+        (Some(tag), Some(project_path)) => tag.tag(project_path),
+        _ => None,
+    };
+    if let (Some(tag), true) = (&project_tag, args.verbose) {
+        out.println(format!("Project tag: {tag}"));
+    }
+
+    let helix_program = args
+        .helix_program
+        .as_deref()
+        .or(config.helix_program.as_deref())
+        .unwrap_or("hx");
+    let launcher_name = |launcher: Launcher| {
+        let name = launcher.to_possible_value().expect("no skipped variant");
+        name.get_name().to_owned()
+    };
+    if let Some(launcher) = args.launcher.filter(|launcher| !launcher.is_supported()) {
+        return Err(Error::UnsupportedLauncher(launcher_name(launcher)));
+    }
+    if let (Some(launcher), Some(_), None, None) = (
+        args.launcher,
+        &project_tag,
+        &args.run_command,
+        &args.execute_path,
+    ) {
+        // The tag is searched in the title, an untitled window would never be found again.
+        if !launcher.has_title() {
+            return Err(Error::UntitledLauncher(launcher_name(launcher)));
+        }
+    }
+    let launch_preset = args.launcher.map(|launcher| {
+        let title = args.window_title.as_deref().unwrap_or("Helix");
+        let title = match &project_tag {
+            Some(tag) => format!("{title} {tag}"),
+            None => title.to_owned(),
+        };
+        launcher.preset(
+            &title,
+            helix_program,
+            args.wsl_distro.as_deref(),
+            args.project_path.is_some(),
+        )
+    });
+    let window_title = args.window_title.clone().or_else(|| {
+        launch_preset
+            .as_ref()
+            .and_then(|preset| preset.window_title.clone())
+    });
+    let window_process_name = args
+        .window_process_name
+        .clone()
+        .or_else(|| {
+            launch_preset
+                .as_ref()
+                .and_then(|preset| preset.window_process_name.clone())
+        })
+        // Terminal backends see the program in the pane, desktop windows belong to the terminal.
+        .or_else(|| {
+            desktop
+                .is_none()
+                .then(|| launcher::program_name(helix_program).to_owned())
+        });
+
+    let pattern = |s: &String| Pattern::new(s, args.match_mode, !args.ignore_case);
+    let patterns = |v: &[String]| v.iter().map(pattern).collect::<Result<Vec<_>>>();
+    let matcher = || {
+        Ok::<_, Error>(WindowMatcher {
+            title: window_title.as_ref().map(pattern).transpose()?,
+            process_name: window_process_name.as_ref().map(pattern).transpose()?,
+            exclude_title: patterns(&args.exclude_title)?,
+            exclude_process_name: patterns(&args.exclude_process_name)?,
+            project_tag: project_tag.as_deref().map(tag_pattern).transpose()?,
+        })
+    };
+    let mut is_searching = !matches!((&window_title, &window_process_name), (None, None));
+    let matcher = match matcher() {
+        Ok(matcher) => matcher,
+        Err(e) if args.list_windows => {
+            out.eprintln(format!("Invalid search, listing without it: {e}"));
+            is_searching = false;
+            WindowMatcher::default()
+        }
+        Err(e) => return Err(e),
+    };
+
+    let ranker = Ranker {
+        title: window_title.clone(),
+        process_name: window_process_name.clone(),
+        case_sensitive: !args.ignore_case,
+        project_name: args
+            .project_path
+            .as_deref()
+            .and_then(project_name)
+            .map(str::to_owned),
+        preferred_process_names: config
+            .preferred_process_names
+            .iter()
+            .chain(&args.prefer_process)
+            .cloned()
+            .collect(),
+    };
+    let search = WindowSearch {
+        ignore: (!args.all).then(|| config.ignore.clone()),
+        matcher,
+        ranker,
+        launched: state.windows_for_project(args.project_path.as_deref()),
+        pick: args.pick,
+    };
+
+    if args.list_windows {
+        let res = list_windows(
+            desktop.as_deref(),
+            &search,
+            is_searching,
+            args.list_format,
+            out,
+        );
+        if !is_searching {
+            return res;
+        }
+    }
+
+    if !is_searching {
+        return Err(Error::IncompleteSearchArgument);
+    }
+
+    let location = match (args.file_path.take(), args.location.take()) {
+        (Some(file_path), _) => {
+            Some(Location::parse_file_uri(&file_path).unwrap_or_else(|| Location::new(file_path)))
+        }
+        (None, Some(location)) => {
+            Some(Location::parse(&location, args.line_base, args.column_base))
+        }
+        (None, None) => None,
+    };
+    let location = location
+        .map(|mut location| {
+            if let Some(resolved) =
+                godot::resolve_path(args.project_path.as_deref(), &location.path)?
+            {
+                location.path = resolved;
+            }
+            if let Some(line) = args.line {
+                location.line = Some(to_one_based(line, args.line_base));
+                location.end_line = None;
+            }
+            if let Some(column) = args.column {
+                location.column = Some(to_one_based(column, args.column_base));
+            }
+            Ok::<_, Error>(location)
+        })
+        .transpose()?;
+    let relative_file_path = match (&location, &args.project_path) {
+        (Some(location), Some(project_path)) => {
+            relative_path(project_path, &location.path, args.relative_parent)
+        }
+        _ => None,
+    };
+
+    let open_file_steps = location.as_ref().map(|location| {
+        let file_path = match &relative_file_path {
+            Some(relative_file_path) if args.relative => relative_file_path,
+            _ => &location.path,
+        };
+        let file_path = convert_path(file_path);
+        let line = location.line.unwrap_or(1);
+        let column = location.column.unwrap_or(1);
+        let mut steps = keyboard_macro::helix_open_file(&file_path, line, column);
+        if let Some(end_line) = location.end_line.filter(|end_line| *end_line > line) {
+            steps.extend(keyboard_macro::helix_select_lines(end_line - line + 1));
+        }
+        steps
+    });
+    // `execute_path` is a program path, not a command line to split.
+    let verbatim = args.run_command.is_none() && args.execute_path.is_some();
+    let run_command =
+        args.run_command
+            .take()
+            .or_else(|| args.execute_path.take().map(|p| vec![p]))
+            .or_else(|| launch_preset.map(|preset| vec![preset.command]))
+            .map(|command| RunCommand {
+                command,
+                verbatim,
+                mode: args.run_command_mode,
+                working_directory: args.run_command_working_directory.clone(),
+                env: args.run_command_env.clone(),
+                placeholders: run_command_placeholders(
+                    args.project_path.as_deref(),
+                    location.as_ref(),
+                    relative_file_path.as_deref(),
+                    &convert_path,
+                ),
+                args: match args.launch_mode {
+                    LaunchMode::Macro => vec![],
+                    LaunchMode::Args => {
+                        let project_path = args.project_path.as_deref().map(convert_path);
+                        let file_path = location
+                            .as_ref()
+                            .map(|location| convert_path(&location.path));
+                        let file = file_path.as_deref().zip(location.as_ref()).map(
+                            |(file_path, location)| {
+                                let line = location.line.unwrap_or(1);
+                                (file_path, line, location.column.unwrap_or(1))
+                            },
+                        );
+                        helix_args(project_path.as_deref(), file)
+                    }
+                },
+            });
+    let request = Request {
+        project_path: args.project_path.as_deref().map(convert_path),
+        open_file_steps,
+        run_command,
+        run_wait: args.run_command_and_wait.or(args.execute_wait),
+    };
+
+    if let Some(desktop) = &mut desktop {
+        return run_desktop(
+            desktop.as_mut(),
+            &args,
+            &search,
+            state_path.as_deref(),
+            &mut state,
+            request,
+            out,
+        );
+    }
+    let backend: Box<dyn Backend> = match args.backend {
+        BackendKind::Windows | BackendKind::X11 => unreachable!("desktop backends are run above"),
+        BackendKind::Tmux => Box::new(Tmux::new(args.tmux_socket.clone(), search.matcher.clone())),
+        BackendKind::Wezterm => Box::new(Wezterm::new(search.matcher.clone())),
+        BackendKind::Kitty => {
+            let address = args
+                .kitty_listen_on
+                .clone()
+                .or_else(|| std::env::var("KITTY_LISTEN_ON").ok())
+                .ok_or_else(|| {
+                    Error::Backend(
+                        "kitty address not given, use `kitty_listen_on` or `KITTY_LISTEN_ON`"
+                            .to_owned(),
+                    )
+                })?;
+            Box::new(Kitty::new(address, search.matcher.clone()))
+        }
+        BackendKind::Zellij => {
+            let session = args
+                .zellij_session
+                .clone()
+                .or_else(|| std::env::var("ZELLIJ_SESSION_NAME").ok())
+                .ok_or_else(|| {
+                    Error::Backend(
+                        "zellij session not given, use `zellij_session` or `ZELLIJ_SESSION_NAME`"
+                            .to_owned(),
+                    )
+                })?;
+            Box::new(Zellij::new(
+                session,
+                args.zellij_tab.clone(),
+                search.matcher.clone(),
+            ))
+        }
+    };
+    run_backend(backend, &args, request, out)
+}
+
+/// What to do once the arguments are resolved, shared by all backends.
+struct Request {
+    /// Project path as Helix sees it, to change directory to after launching.
+    project_path: Option<String>,
+    open_file_steps: Option<Vec<Step>>,
+    run_command: Option<RunCommand>,
+    run_wait: Option<f64>,
+}
+
+fn run_backend(
+    mut backend: Box<dyn Backend>,
+    args: &Args,
+    request: Request,
+    out: &mut Output,
+) -> Result<()> {
+    let change_directory = match (backend.focus(), request.run_command) {
+        (Err(Error::WindowNotFound), Some(run_command)) => {
+            spawn_run_command(&run_command, request.run_wait, args.verbose, out)?;
+            if let Some(timeout) = args.run_command_timeout {
+                wait_for(
+                    timeout,
+                    args.run_command_poll_interval,
+                    args.verbose,
+                    out,
+                    || backend.is_open(),
+                )?;
+                sleep(args.run_command_ready_wait);
+            }
+            out.println(format!("Focusing {}", backend.focus()?));
+            if args.no_init_macro || args.launch_mode == LaunchMode::Args {
+                return Ok(());
+            }
+            request.project_path
+        }
+        (res, _) => {
+            out.println(format!("Focusing {}", res?));
+            None
+        }
+    };
+    if let Some(project_path) = &change_directory {
+        backend.send(&keyboard_macro::helix_change_directory(project_path))?;
+    }
+    if let Some(steps) = &request.open_file_steps {
+        backend.send(steps)?;
+    }
+    Ok(())
+}
+
+/// Desktop of a desktop backend, `None` for backends driving a terminal.
+fn open_desktop(args: &Args) -> Result<Option<Box<dyn Desktop>>> {
+    match args.backend {
+        #[cfg(all(windows, feature = "windows"))]
+        BackendKind::Windows => Ok(Some(Box::new(WindowsDesktop {
+            clipboard: args.clipboard,
+            focus_check: !args.no_focus_check,
+        }))),
+        #[cfg(not(all(windows, feature = "windows")))]
+        BackendKind::Windows => Err(Error::Backend(
+            "windows backend is not included in this build, choose another with `backend`"
+                .to_owned(),
+        )),
+        #[cfg(unix)]
+        BackendKind::X11 => Ok(Some(Box::new(X11::connect(!args.no_focus_check)?))),
+        #[cfg(not(unix))]
+        BackendKind::X11 => Err(Error::Backend(
+            "X11 backend is only available on Unix".to_owned(),
+        )),
+        BackendKind::Tmux | BackendKind::Wezterm | BackendKind::Kitty | BackendKind::Zellij => {
+            Ok(None)
+        }
+    }
+}
+
+fn run_desktop(
+    desktop: &mut dyn Desktop,
+    args: &Args,
+    search: &WindowSearch,
+    state_path: Option<&Path>,
+    state: &mut State,
+    request: Request,
+    out: &mut Output,
+) -> Result<()> {
+    let res = focus_window(desktop, search, args.verbose, out);
+    let (window, change_directory) = match (res, request.run_command) {
+        (Err(Error::WindowNotFound), Some(run_command)) => {
+            let child = spawn_run_command(&run_command, request.run_wait, args.verbose, out)?;
+            if let Some(timeout) = args.run_command_timeout {
+                wait_for(
+                    timeout,
+                    args.run_command_poll_interval,
+                    args.verbose,
+                    out,
+                    || {
+                        let windows = search.windows(desktop)?;
+                        Ok(windows.iter().any(|info| search.is_match(info)))
+                    },
+                )?;
+                sleep(args.run_command_ready_wait);
+            }
+            let window = focus_window(desktop, search, args.verbose, out)?;
+            if let Some(state_path) = state_path {
+                state.add(Instance::new(
+                    child.id(),
+                    args.project_path.clone(),
+                    WindowIdentity::of(&window),
+                ));
+                save_state(state, state_path, out);
+            }
+            if args.no_init_macro || args.launch_mode == LaunchMode::Args {
+                return Ok(());
+            }
+            (window, request.project_path)
+        }
+        (res, _) => (res?, None),
+    };
+
+    if let Some(project_path) = &change_directory {
+        desktop.play(
+            &window,
+            &keyboard_macro::helix_change_directory(project_path),
+        )?;
+    }
+
+    sleep(0.1);
+
+    if let Some(steps) = request.open_file_steps {
+        if change_directory.is_some() {
+            sleep(0.1);
+        }
+        desktop.play(&window, &steps)?;
+    }
+
+    Ok(())
+}
+
+/// Spawn `run_command` and wait `wait` seconds if given.
+fn spawn_run_command(
+    run_command: &RunCommand,
+    wait: Option<f64>,
+    verbose: bool,
+    out: &mut Output,
+) -> Result<Child> {
+    if verbose {
+        out.println(format!("Running {:?}", run_command.program_args()?));
+    }
+    let child = run_command.spawn()?;
+    if let Some(wait) = wait {
+        sleep(wait);
+    }
+    Ok(child)
+}
+
+fn run_command_placeholders(
+    project_path: Option<&str>,
+    location: Option<&Location>,
+    relative_file_path: Option<&str>,
+    convert_path: &impl Fn(&str) -> String,
+) -> Vec<(&'static str, String)> {
+    let file_path = location.map(|location| location.path.as_str());
+    // Line and column default to 1 when there is a file, so `{file}:{line}:{col}` stays valid.
+    let number = |n: fn(&Location) -> Option<u32>| {
+        location
+            .map(|location| n(location).unwrap_or(1).to_string())
+            .unwrap_or_default()
+    };
+    vec![
+        (
+            "project",
+            project_path.map(convert_path).unwrap_or_default(),
+        ),
+        ("file", file_path.map(convert_path).unwrap_or_default()),
+        (
+            "file_rel",
+            relative_file_path
+                .or(file_path)
+                .map(convert_path)
+                .unwrap_or_default(),
+        ),
+        ("line", number(|location| location.line)),
+        ("col", number(|location| location.column)),
+        (
+            "wsl_file",
+            file_path
+                .map(|file_path| PathStyle::Wsl.convert(file_path))
+                .unwrap_or_default(),
+        ),
+        (
+            "project_name",
+            project_path
+                .and_then(project_name)
+                .map(str::to_owned)
+                .unwrap_or_default(),
+        ),
+        (
+            "title_token",
+            project_path.map(title_token).unwrap_or_default(),
+        ),
+    ]
+}
+
+/// Save state, only warning on failure as it's not needed for the rest to work.
+fn save_state(state: &State, path: &Path, out: &mut Output) {
+    if let Err(e) = state.save(path) {
+        out.eprintln(format!("Failed to save state to {}: {e}", path.display()));
+    }
+}
+
+fn list_windows(
+    desktop: Option<&dyn Desktop>,
+    search: &WindowSearch,
+    is_searching: bool,
+    format: ListFormat,
+    out: &mut Output,
+) -> Result<()> {
+    let desktop = desktop.ok_or_else(|| {
+        Error::Backend(
+            "listing windows needs a desktop backend, choose one with `backend`".to_owned(),
+        )
+    })?;
+    let windows: Vec<_> = search
+        .windows(desktop)?
+        .into_iter()
+        .map(|info| {
+            let is_match = is_searching && search.is_match(&info);
+            (info, is_match)
+        })
+        .collect();
+    out.print(&format_window_list(&windows, format));
+    Ok(())
+}
+
+/// Call `is_found` until it returns true or `timeout` seconds passed.
+fn wait_for(
+    timeout: f64,
+    interval: f64,
+    verbose: bool,
+    out: &mut Output,
+    mut is_found: impl FnMut() -> Result<bool>,
+) -> Result<()> {
+    let start = Instant::now();
+    loop {
+        if is_found()? {
+            if verbose {
+                out.println(format!(
+                    "Window appeared after {:.2}s",
+                    start.elapsed().as_secs_f64()
+                ));
+            }
+            return Ok(());
+        }
+        if start.elapsed().as_secs_f64() >= timeout {
+            return Err(Error::WindowTimeout(timeout));
+        }
+        sleep(interval);
+    }
+}
+
+/// Focus the window chosen by `search`.
+fn focus_window(
+    desktop: &mut dyn Desktop,
+    search: &WindowSearch,
+    verbose: bool,
+    out: &mut Output,
+) -> Result<WindowInfo> {
+    let mut candidates = search.candidates(search.windows(desktop)?);
+
+    if verbose {
+        out.println(format!("{} windows matched:", candidates.len()));
+        for (score, info) in &candidates {
+            out.println(format!(
+                "  [{}] {} ({score})",
+                info.process_name, info.title
+            ));
+        }
+    }
+
+    let index = match search.pick {
+        Pick::Fail if candidates.len() > 1 => return Err(Error::AmbiguousWindow(candidates.len())),
+        Pick::Ask if candidates.len() > 1 => ask_window(&candidates)?,
+        _ => 0,
+    };
+    if index >= candidates.len() {
+        return Err(Error::WindowNotFound);
+    }
+    let (score, info) = candidates.swap_remove(index);
+
+    if verbose {
+        let reason = match search.pick {
+            Pick::First => "first by process name".to_owned(),
+            Pick::Ask => "picked".to_owned(),
+            Pick::Best | Pick::Fail => format!("best score, {score}"),
+        };
+        out.println(format!(
+            "Picked [{}] {} ({reason})",
+            info.process_name, info.title
+        ));
+    }
+    out.println(format!("Focusing [{}] {}", info.process_name, info.title));
+
+    desktop.focus(&info)?;
+    Ok(info)
+}
+
+/// Ask in the terminal, requests to the daemon can't pick, see [`run_request`].
+fn ask_window(candidates: &[(Score, WindowInfo)]) -> Result<usize> {
+    use std::io::{BufRead, Write};
+    for (i, (_score, info)) in candidates.iter().enumerate() {
+        println!("{}: [{}] {}", i + 1, info.process_name, info.title);
+    }
+    let stdin = std::io::stdin();
+    loop {
+        print!("Pick a window (1-{}): ", candidates.len());
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        // Closing the prompt must not launch a new window like "not found" would.
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Err(Error::Cancelled);
+        }
+        match line.trim().parse::<usize>() {
+            Ok(n) if (1..=candidates.len()).contains(&n) => return Ok(n - 1),
+            _ => continue,
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use super::{run_command_placeholders, run_request, wait_for, Output};
+use crate::{config::Config, daemon::Request, error::Error, location::Location};
+
+#[test]
+fn test_run_command_placeholders() {
+    let location = Location {
+        line: Some(12),
+        ..Location::new("C:/my game/a.gd".to_owned())
+    };
+    let upper = |path: &str| path.to_uppercase();
+    let placeholders =
+        run_command_placeholders(Some("C:/my game"), Some(&location), Some("a.gd"), &upper);
+    let value = |name: &str| {
+        let (_, value) = placeholders.iter().find(|(n, _)| *n == name).unwrap();
+        value.as_str()
+    };
+    assert_eq!(value("project"), "C:/MY GAME");
+    assert_eq!(value("file"), "C:/MY GAME/A.GD");
+    assert_eq!(value("file_rel"), "A.GD");
+    assert_eq!(value("line"), "12");
+    assert_eq!(value("col"), "1");
+    assert_eq!(value("wsl_file"), "/mnt/c/my game/a.gd");
+    assert_eq!(value("project_name"), "my game");
+
+    // No file leaves the file placeholders empty instead of pointing at line 1.
+    let placeholders = run_command_placeholders(None, None, None, &upper);
+    assert!(placeholders.iter().all(|(_, value)| value.is_empty()));
+}
+
+#[test]
+fn test_wait_for() {
+    let mut out = Output::captured();
+    let mut calls = 0;
+    wait_for(1.0, 0.0, true, &mut out, || {
+        calls += 1;
+        Ok(calls == 3)
+    })
+    .unwrap();
+    assert_eq!(calls, 3);
+    assert!(out.stdout.starts_with("Window appeared after "));
+
+    assert!(matches!(
+        wait_for(0.05, 0.01, false, &mut out, || Ok(false)),
+        Err(Error::WindowTimeout(_))
+    ));
+}
+
+fn request(args: &[&str], working_directory: Option<PathBuf>) -> Request {
+    Request {
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        working_directory,
+    }
+}
+
+#[test]
+fn test_run_request() {
+    let config = Config::default();
+    let run = |request| run_request(request, &config, &mut Output::captured());
+    let current_dir = std::env::current_dir().unwrap();
+
+    // Parsed and run, only to find that listing needs a desktop backend.
+    let args = [
+        "--list",
+        "--backend",
+        "tmux",
+        "--match-mode",
+        "regex",
+        "-t",
+        "(",
+    ];
+    let listing = request(&args, Some(current_dir));
+    assert!(matches!(run(listing), Err(Error::Backend(_))));
+
+    assert!(matches!(
+        run(request(&["--no-such-flag"], None)),
+        Err(Error::Daemon(_))
+    ));
+    assert!(matches!(
+        run(request(&["--config", "other.toml", "-t", "Helix"], None)),
+        Err(Error::Daemon(_))
+    ));
+    assert!(matches!(
+        run(request(&["--pick", "ask", "-t", "Helix"], None)),
+        Err(Error::Daemon(_))
+    ));
+}
+
+/// A relative working directory would be taken from the daemon's directory.
+#[test]
+fn test_run_request_relative_working_directory() {
+    let current_dir = std::env::current_dir().unwrap();
+    let request = request(&["-t", "Helix"], Some(PathBuf::from("my_game")));
+    let error = run_request(request, &Config::default(), &mut Output::captured()).unwrap_err();
+    assert!(error.to_string().contains("`my_game` is not absolute"));
+    assert_eq!(std::env::current_dir().unwrap(), current_dir);
+}
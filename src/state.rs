@@ -0,0 +1,140 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{error::Result, path::normalize_path, window_info::WindowInfo};
+
+#[cfg(test)]
+mod test;
+
+/// Helix instances launched by `run`, kept between invocations
+/// so the exact windows can be used again.
+///
+/// Default location is `<local data dir>/helix-win-runner/state.json`,
+/// which is `%LOCALAPPDATA%\helix-win-runner\state.json` on Windows.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    pub instances: Vec<Instance>,
+}
+
+/// Helix instance launched by `run`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Instance {
+    /// Seconds since the Unix epoch.
+    pub launched_at: u64,
+    /// Process spawned by `run`, usually a terminal launcher that exited already.
+    pub spawned_pid: u32,
+    /// Project path given when launched.
+    pub project: Option<String>,
+    pub window: WindowIdentity,
+}
+
+/// What identifies a window between invocations.
+/// Handles and process ids can be reused, so all of them must match.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct WindowIdentity {
+    pub handle: usize,
+    pub process_id: u32,
+    pub process_name: String,
+}
+
+impl WindowIdentity {
+    pub fn of(info: &WindowInfo) -> WindowIdentity {
+        WindowIdentity {
+            handle: info.handle,
+            process_id: info.process_id,
+            process_name: info.process_name.clone(),
+        }
+    }
+
+    pub fn is(&self, info: &WindowInfo) -> bool {
+        self.handle == info.handle
+            && self.process_id == info.process_id
+            && self.process_name == info.process_name
+    }
+}
+
+impl Instance {
+    /// Instance launched now.
+    pub fn new(spawned_pid: u32, project: Option<String>, window: WindowIdentity) -> Instance {
+        let launched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        Instance {
+            launched_at,
+            spawned_pid,
+            project,
+            window,
+        }
+    }
+
+    /// Whether the instance was launched for `project`, comparing normalized paths.
+    pub fn is_for_project(&self, project: Option<&str>) -> bool {
+        match (&self.project, project) {
+            (Some(a), Some(b)) => normalize_path(a) == normalize_path(b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl State {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|dir| dir.join("helix-win-runner").join("state.json"))
+    }
+
+    /// Load state from `path`.
+    /// A missing or unreadable file gives an empty state, it's only a cache.
+    pub fn load(path: &Path) -> State {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save state to `path`.
+    /// Written to a temporary file renamed over `path`, so a crash can't leave it truncated.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let content = serde_json::to_string_pretty(self).expect("state to serialize");
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(format!(".{}.tmp", std::process::id()));
+        let temp_path = PathBuf::from(temp_path);
+        let res =
+            std::fs::write(&temp_path, content).and_then(|()| std::fs::rename(&temp_path, path));
+        if res.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        Ok(res?)
+    }
+
+    /// Remove instances whose window is not in `windows` anymore.
+    /// Returns whether any was removed.
+    pub fn prune(&mut self, windows: &[WindowInfo]) -> bool {
+        let len = self.instances.len();
+        self.instances
+            .retain(|instance| windows.iter().any(|info| instance.window.is(info)));
+        self.instances.len() != len
+    }
+
+    /// Add `instance`, replacing any instance with the same window.
+    pub fn add(&mut self, instance: Instance) {
+        self.instances
+            .retain(|existing| existing.window != instance.window);
+        self.instances.push(instance);
+    }
+
+    /// Windows of instances launched for `project`.
+    pub fn windows_for_project(&self, project: Option<&str>) -> Vec<WindowIdentity> {
+        self.instances
+            .iter()
+            .filter(|instance| instance.is_for_project(project))
+            .map(|instance| instance.window.clone())
+            .collect()
+    }
+}
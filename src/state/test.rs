@@ -0,0 +1,118 @@
+use super::{Instance, State, WindowIdentity};
+use crate::window_info::WindowInfo;
+
+fn window(handle: usize, process_id: u32) -> WindowInfo {
+    WindowInfo {
+        handle,
+        process_id,
+        thread_id: 1,
+        process_name: "WindowsTerminal.exe".to_owned(),
+        title: "Helix".to_owned(),
+        visible: true,
+        width: 800,
+        height: 600,
+        tool_window: false,
+    }
+}
+
+fn instance(handle: usize, process_id: u32, project: Option<&str>) -> Instance {
+    Instance {
+        launched_at: 1700000000,
+        spawned_pid: 42,
+        project: project.map(str::to_owned),
+        window: WindowIdentity::of(&window(handle, process_id)),
+    }
+}
+
+#[test]
+fn test_prune() {
+    let mut state = State {
+        instances: vec![
+            instance(0x10, 1, None),
+            instance(0x20, 2, None),
+            instance(0x30, 3, None),
+        ],
+    };
+    // 0x20 closed, 0x30 reused by another process.
+    let windows = [window(0x10, 1), window(0x30, 4)];
+    assert!(state.prune(&windows));
+    assert_eq!(state.instances, [instance(0x10, 1, None)]);
+    assert!(!state.prune(&windows));
+}
+
+#[test]
+fn test_add_replaces_same_window() {
+    let mut state = State::default();
+    state.add(instance(0x10, 1, Some("C:/a")));
+    state.add(instance(0x20, 2, Some("C:/b")));
+    state.add(instance(0x10, 1, Some("C:/c")));
+    let projects: Vec<_> = state
+        .instances
+        .iter()
+        .map(|instance| instance.project.as_deref())
+        .collect();
+    assert_eq!(projects, [Some("C:/b"), Some("C:/c")]);
+}
+
+#[test]
+fn test_windows_for_project() {
+    let state = State {
+        instances: vec![
+            instance(0x10, 1, Some("C:\\Users\\USER\\game")),
+            instance(0x20, 2, None),
+        ],
+    };
+    assert_eq!(
+        state.windows_for_project(Some("c:/Users/USER/game/")),
+        [WindowIdentity::of(&window(0x10, 1))]
+    );
+    assert_eq!(
+        state.windows_for_project(None),
+        [WindowIdentity::of(&window(0x20, 2))]
+    );
+    assert!(state.windows_for_project(Some("C:/other")).is_empty());
+}
+
+#[test]
+fn test_save_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("helix-win-runner").join("state.json");
+    assert_eq!(State::load(&path), State::default());
+
+    let state = State {
+        instances: vec![instance(0x10, 1, Some("C:/a"))],
+    };
+    state.save(&path).unwrap();
+    assert_eq!(State::load(&path), state);
+
+    std::fs::write(&path, "not json").unwrap();
+    assert_eq!(State::load(&path), State::default());
+}
+
+#[test]
+fn test_save_replaces_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state.json");
+    State {
+        instances: vec![instance(0x10, 1, Some("C:/a")), instance(0x20, 2, None)],
+    }
+    .save(&path)
+    .unwrap();
+    let state = State {
+        instances: vec![instance(0x30, 3, None)],
+    };
+    state.save(&path).unwrap();
+    assert_eq!(State::load(&path), state);
+    // Nothing is left besides the state file.
+    let files: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(files, ["state.json"]);
+
+    // Failing to write keeps the old state.
+    let dir_path = dir.path().join("dir");
+    std::fs::create_dir(&dir_path).unwrap();
+    assert!(state.save(&dir_path).is_err());
+    assert_eq!(State::load(&path), state);
+}
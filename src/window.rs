@@ -12,6 +12,8 @@ use winapi::{
     um::{processthreadsapi, winnt, winuser},
 };
 
+use crate::window_info::WindowInfo;
+
 type WindowHandle = NonNull<windef::HWND__>;
 
 pub struct Window {
@@ -53,6 +55,10 @@ impl Window {
         })
     }
 
+    pub fn handle(&self) -> WindowHandle {
+        self.handle
+    }
+
     pub fn thread_id(&self) -> u32 {
         self.thread_id
     }
@@ -79,6 +85,43 @@ impl Window {
         Ok(String::from_utf16_lossy(&buffer))
     }
 
+    /// Return true if this window is the foreground window.
+    pub fn is_foreground(&self) -> bool {
+        // SAFETY: the call doesn't have dangerous side-effects.
+        let foreground = unsafe { winuser::GetForegroundWindow() };
+        foreground == self.handle.as_ptr()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        // SAFETY: handle is valid
+        FALSE != unsafe { winuser::IsWindowVisible(self.handle.as_ptr()) }
+    }
+
+    /// Window size in pixels.
+    pub fn size(&self) -> IoResult<(u32, u32)> {
+        let mut rect = windef::RECT {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        };
+        // SAFETY: handle and rect are valid
+        let res = unsafe { winuser::GetWindowRect(self.handle.as_ptr(), &mut rect) };
+        if res == FALSE {
+            return last_os_error();
+        }
+        let width = rect.right.saturating_sub(rect.left).max(0) as u32;
+        let height = rect.bottom.saturating_sub(rect.top).max(0) as u32;
+        Ok((width, height))
+    }
+
+    pub fn is_tool_window(&self) -> bool {
+        // SAFETY: handle is valid
+        let ex_style =
+            unsafe { winuser::GetWindowLongW(self.handle.as_ptr(), winuser::GWL_EXSTYLE) };
+        ex_style as u32 & winuser::WS_EX_TOOLWINDOW != 0
+    }
+
     // If the window was previously visible, the return value is false.
     // If the window was previously hidden, the return value is true.
 
@@ -182,7 +225,6 @@ impl Window {
 }
 
 pub struct Process {
-    #[allow(dead_code)]
     process_id: u32,
     handle: NonNull<c_void>,
 }
@@ -197,6 +239,10 @@ impl Process {
             .ok_or_else(IoError::last_os_error)
     }
 
+    pub fn process_id(&self) -> u32 {
+        self.process_id
+    }
+
     pub fn name(&self, capacity: usize) -> IoResult<String> {
         // EnumProcessModules takes a pointer to an array of HMODULE.
         // We could use a Vec of capacity one to hold the single module,
@@ -290,7 +336,8 @@ fn last_os_error<T>() -> IoResult<T> {
     Err(IoError::last_os_error())
 }
 
-pub fn get_windows(all: bool) -> IoResult<Vec<(String, String, Window)>> {
+/// Get windows with their information in z-order, topmost first.
+pub fn get_windows() -> IoResult<Vec<(WindowInfo, Window)>> {
     enumerate_windows()?
         .into_iter()
         .filter_map(|window_handle| {
@@ -300,11 +347,19 @@ pub fn get_windows(all: bool) -> IoResult<Vec<(String, String, Window)>> {
                 Err(e) => return Some(Err(e)),
             };
             let title = window.title(256).ok()?;
-            if all || !(title.contains("Default IME") || title.contains("MSCTFIME UI")) {
-                Some(Ok((process_name, title, window)))
-            } else {
-                None
-            }
+            let (width, height) = window.size().unwrap_or((0, 0));
+            let info = WindowInfo {
+                handle: window.handle().as_ptr() as usize,
+                process_id: window.process().process_id(),
+                thread_id: window.thread_id(),
+                process_name,
+                title,
+                visible: window.is_visible(),
+                width,
+                height,
+                tool_window: window.is_tool_window(),
+            };
+            Some(Ok((info, window)))
         })
         .collect()
 }
@@ -0,0 +1,174 @@
+use std::fmt::Write;
+
+#[cfg(test)]
+mod test;
+
+/// Information about a window, independent of the window backend.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct WindowInfo {
+    /// Native window handle.
+    pub handle: usize,
+    pub process_id: u32,
+    pub thread_id: u32,
+    pub process_name: String,
+    pub title: String,
+    pub visible: bool,
+    pub width: u32,
+    pub height: u32,
+    /// Tool windows are floating toolbars, not shown in the taskbar.
+    pub tool_window: bool,
+}
+
+/// Windows to skip when searching and listing, configured by `ignore` in the config.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IgnoreList {
+    /// Skip windows with title containing any of these.
+    pub titles: Vec<String>,
+    /// Skip windows with process name containing any of these.
+    pub process_names: Vec<String>,
+    /// Skip invisible windows.
+    pub invisible: bool,
+    /// Skip windows with no width or height.
+    pub zero_size: bool,
+    /// Skip tool windows.
+    pub tool_windows: bool,
+}
+
+impl Default for IgnoreList {
+    fn default() -> Self {
+        IgnoreList {
+            titles: vec!["Default IME".to_owned(), "MSCTFIME UI".to_owned()],
+            process_names: vec![],
+            invisible: false,
+            zero_size: false,
+            tool_windows: false,
+        }
+    }
+}
+
+impl IgnoreList {
+    pub fn is_ignored(&self, info: &WindowInfo) -> bool {
+        self.titles.iter().any(|title| info.title.contains(title))
+            || self
+                .process_names
+                .iter()
+                .any(|process_name| info.process_name.contains(process_name))
+            || (self.invisible && !info.visible)
+            || (self.zero_size && (info.width == 0 || info.height == 0))
+            || (self.tool_windows && info.tool_window)
+    }
+}
+
+/// Output format of the window list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListFormat {
+    /// `[<process name>] <window title>` lines.
+    #[default]
+    Plain,
+    /// JSON array of objects.
+    Json,
+    /// Tab separated values with a header line.
+    Tsv,
+    /// Aligned columns for reading.
+    Table,
+}
+
+#[derive(serde::Serialize)]
+struct ListedWindow<'a> {
+    #[serde(flatten)]
+    info: &'a WindowInfo,
+    matches: bool,
+}
+
+/// Same names as the JSON fields.
+const HEADER: [&str; 10] = [
+    "handle",
+    "process_id",
+    "thread_id",
+    "process_name",
+    "title",
+    "visible",
+    "width",
+    "height",
+    "tool_window",
+    "matches",
+];
+
+/// Format windows with whether they match the search.
+pub fn format_window_list(windows: &[(WindowInfo, bool)], format: ListFormat) -> String {
+    let mut output = String::new();
+    match format {
+        ListFormat::Plain => {
+            for (info, _) in windows {
+                let _ = writeln!(output, "[{}] {}", info.process_name, info.title);
+            }
+        }
+        ListFormat::Json => {
+            let windows: Vec<_> = windows
+                .iter()
+                .map(|(info, matches)| ListedWindow {
+                    info,
+                    matches: *matches,
+                })
+                .collect();
+            output = serde_json::to_string_pretty(&windows).expect("window list to serialize");
+            output.push('\n');
+        }
+        ListFormat::Tsv => {
+            output.push_str(&HEADER.join("\t"));
+            output.push('\n');
+            for row in rows(windows) {
+                let row: Vec<_> = row.iter().map(|cell| escape_tsv(cell)).collect();
+                output.push_str(&row.join("\t"));
+                output.push('\n');
+            }
+        }
+        ListFormat::Table => {
+            let header = HEADER.map(str::to_owned);
+            let rows: Vec<_> = std::iter::once(header).chain(rows(windows)).collect();
+            let mut widths = [0; HEADER.len()];
+            for row in &rows {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+            for row in &rows {
+                let mut line = String::new();
+                for (i, (cell, width)) in row.iter().zip(widths).enumerate() {
+                    if i > 0 {
+                        line.push_str("  ");
+                    }
+                    let _ = write!(line, "{cell:width$}");
+                }
+                output.push_str(line.trim_end());
+                output.push('\n');
+            }
+        }
+    }
+    output
+}
+
+fn rows(windows: &[(WindowInfo, bool)]) -> impl Iterator<Item = [String; HEADER.len()]> + '_ {
+    windows.iter().map(|(info, matches)| {
+        [
+            format!("{:#x}", info.handle),
+            info.process_id.to_string(),
+            info.thread_id.to_string(),
+            info.process_name.clone(),
+            info.title.clone(),
+            info.visible.to_string(),
+            info.width.to_string(),
+            info.height.to_string(),
+            info.tool_window.to_string(),
+            matches.to_string(),
+        ]
+    })
+}
+
+fn escape_tsv(cell: &str) -> String {
+    cell.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
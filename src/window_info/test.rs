@@ -0,0 +1,130 @@
+use super::{format_window_list, IgnoreList, ListFormat, WindowInfo};
+
+fn windows() -> Vec<(WindowInfo, bool)> {
+    vec![
+        (
+            WindowInfo {
+                handle: 0x1a2b,
+                process_id: 100,
+                thread_id: 200,
+                process_name: "WindowsTerminal.exe".to_owned(),
+                title: "Helix".to_owned(),
+                visible: true,
+                width: 800,
+                height: 600,
+                tool_window: false,
+            },
+            true,
+        ),
+        (
+            WindowInfo {
+                handle: 0x30,
+                process_id: 7,
+                thread_id: 8,
+                process_name: "firefox.exe".to_owned(),
+                title: "a\tb".to_owned(),
+                visible: false,
+                width: 0,
+                height: 0,
+                tool_window: true,
+            },
+            false,
+        ),
+    ]
+}
+
+#[test]
+fn test_format_plain() {
+    assert_eq!(
+        format_window_list(&windows(), ListFormat::Plain),
+        "[WindowsTerminal.exe] Helix\n[firefox.exe] a\tb\n"
+    );
+}
+
+#[test]
+fn test_format_tsv() {
+    assert_eq!(
+        format_window_list(&windows(), ListFormat::Tsv),
+        "handle\tprocess_id\tthread_id\tprocess_name\ttitle\t\
+         visible\twidth\theight\ttool_window\tmatches\n\
+         0x1a2b\t100\t200\tWindowsTerminal.exe\tHelix\ttrue\t800\t600\tfalse\ttrue\n\
+         0x30\t7\t8\tfirefox.exe\ta\\tb\tfalse\t0\t0\ttrue\tfalse\n"
+    );
+}
+
+#[test]
+fn test_format_json() {
+    let json = format_window_list(&windows(), ListFormat::Json);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value[0]["handle"], 0x1a2b);
+    assert_eq!(value[0]["process_id"], 100);
+    assert_eq!(value[0]["thread_id"], 200);
+    assert_eq!(value[0]["process_name"], "WindowsTerminal.exe");
+    assert_eq!(value[0]["title"], "Helix");
+    assert_eq!(value[0]["visible"], true);
+    assert_eq!(value[0]["width"], 800);
+    assert_eq!(value[0]["height"], 600);
+    assert_eq!(value[0]["tool_window"], false);
+    assert_eq!(value[0]["matches"], true);
+    assert_eq!(value[1]["matches"], false);
+}
+
+/// TSV, the table and JSON all list the same fields.
+#[test]
+fn test_formats_have_same_fields() {
+    let windows = windows();
+    let json = format_window_list(&windows, ListFormat::Json);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let mut json_fields: Vec<_> = value[0].as_object().unwrap().keys().cloned().collect();
+    json_fields.sort();
+
+    let tsv = format_window_list(&windows, ListFormat::Tsv);
+    let table = format_window_list(&windows, ListFormat::Table);
+    for header in [
+        tsv.lines().next().unwrap().split('\t').collect::<Vec<_>>(),
+        table.lines().next().unwrap().split_whitespace().collect(),
+    ] {
+        let mut fields: Vec<_> = header.iter().map(|field| field.to_string()).collect();
+        fields.sort();
+        assert_eq!(fields, json_fields);
+    }
+}
+
+#[test]
+fn test_format_table() {
+    let table = format_window_list(&windows(), ListFormat::Table);
+    let lines: Vec<_> = table.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("handle  process_id  thread_id  process_name"));
+    assert!(lines[1].starts_with("0x1a2b  100         200        WindowsTerminal.exe  Helix"));
+}
+
+#[test]
+fn test_ignore_list() {
+    let windows = windows();
+    let (helix, other) = (&windows[0].0, &windows[1].0);
+    let ime = WindowInfo {
+        title: "Default IME".to_owned(),
+        ..helix.clone()
+    };
+
+    let ignore = IgnoreList::default();
+    assert!(ignore.is_ignored(&ime));
+    assert!(!ignore.is_ignored(helix));
+    assert!(!ignore.is_ignored(other));
+
+    let ignore = |f: fn(&mut IgnoreList)| {
+        let mut ignore = IgnoreList {
+            titles: vec![],
+            ..Default::default()
+        };
+        f(&mut ignore);
+        ignore
+    };
+    assert!(!ignore(|_| {}).is_ignored(&ime));
+    assert!(ignore(|i| i.invisible = true).is_ignored(other));
+    assert!(ignore(|i| i.zero_size = true).is_ignored(other));
+    assert!(ignore(|i| i.tool_windows = true).is_ignored(other));
+    assert!(!ignore(|i| i.tool_windows = true).is_ignored(helix));
+    assert!(ignore(|i| i.process_names = vec!["firefox".to_owned()]).is_ignored(other));
+}